@@ -0,0 +1,163 @@
+//! Statistical constant-time checks in the style of dudect.
+//!
+//! Run with `cargo bench --features ct-test --bench ct`.
+//!
+//! Every operation is timed on a fixed secret input and on random secret
+//! inputs, with the two classes interleaved at random. Welch's t-statistic is
+//! then computed over the two timing distributions. An absolute value above
+//! `THRESHOLD` is strong evidence that the running time depends on the secret.
+//! A pass does not prove the absence of a leak; it only means none was found
+//! with this many measurements on this machine.
+use std::time::Instant;
+
+use criterion::black_box;
+use rand::{RngCore, SeedableRng};
+use rand_xorshift::XorShiftRng;
+
+use ff::Field;
+use group::Group;
+use secp256k1forhalo2::{hazmat, Fp, Fq, Secp256k1, WindowTable};
+
+const MEASUREMENTS: usize = 100_000;
+const THRESHOLD: f64 = 4.5;
+
+/// Measurements above this percentile are discarded as interrupts, context
+/// switches and other noise.
+const CROP_PERCENTILE: f64 = 0.9;
+
+/// Online mean and variance of the two classes.
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        (self.mean[0] - self.mean[1]) / (var0 / self.n[0] + var1 / self.n[1]).sqrt()
+    }
+}
+
+/// Times `op` on `fixed` and on inputs drawn from `random`, and prints the
+/// resulting t-statistic. Returns whether a leak was detected.
+fn check<I, R, F, O>(name: &str, rng: &mut XorShiftRng, fixed: I, mut random: R, op: F) -> bool
+where
+    I: Copy,
+    R: FnMut(&mut XorShiftRng) -> I,
+    F: Fn(&I) -> O,
+{
+    let inputs: Vec<(usize, I)> = (0..MEASUREMENTS)
+        .map(|_| {
+            if rng.next_u32() & 1 == 0 {
+                (0, fixed)
+            } else {
+                (1, random(rng))
+            }
+        })
+        .collect();
+
+    let timings: Vec<(usize, u128)> = inputs
+        .iter()
+        .map(|(class, input)| {
+            let start = Instant::now();
+            black_box(op(black_box(input)));
+            (*class, start.elapsed().as_nanos())
+        })
+        .collect();
+
+    let mut sorted: Vec<u128> = timings.iter().map(|(_, t)| *t).collect();
+    sorted.sort_unstable();
+    let crop = sorted[(sorted.len() as f64 * CROP_PERCENTILE) as usize];
+
+    let mut welch = Welch::default();
+    for (class, t) in timings.into_iter().filter(|(_, t)| *t <= crop) {
+        welch.push(class, t as f64);
+    }
+
+    let t = welch.t();
+    let leaks = t.abs() > THRESHOLD;
+    println!(
+        "{:<28} t = {:>8.2}  {}",
+        name,
+        t,
+        if leaks { "LEAK" } else { "ok" }
+    );
+    leaks
+}
+
+fn main() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let base = Secp256k1::random(&mut rng);
+    let table = WindowTable::<4>::new(&base);
+    let x = Fq::random(&mut rng);
+    let z = Fq::random(&mut rng);
+
+    let mut leaks = false;
+    leaks |= check(
+        "Fp::invert",
+        &mut rng,
+        Fp::one(),
+        |rng| Fp::random(rng),
+        |x| x.invert(),
+    );
+    leaks |= check(
+        "Fq::invert",
+        &mut rng,
+        Fq::one(),
+        |rng| Fq::random(rng),
+        |x| x.invert(),
+    );
+    leaks |= check(
+        "Secp256k1 * Fq",
+        &mut rng,
+        Fq::zero(),
+        |rng| Fq::random(rng),
+        |k| base * k,
+    );
+    leaks |= check(
+        "WindowTable::mul",
+        &mut rng,
+        Fq::zero(),
+        |rng| Fq::random(rng),
+        |k| table.mul(k),
+    );
+    leaks |= check(
+        "Fq::pow",
+        &mut rng,
+        [0u64; 4],
+        |rng| {
+            [
+                rng.next_u64(),
+                rng.next_u64(),
+                rng.next_u64(),
+                rng.next_u64(),
+            ]
+        },
+        |exp| Fq::pow(&x, exp),
+    );
+    leaks |= check(
+        "sign_prehashed_with_nonce",
+        &mut rng,
+        (Fq::one(), Fq::one()),
+        |rng| (Fq::random(&mut *rng), Fq::random(rng)),
+        |(secret_key, nonce)| hazmat::sign_prehashed_with_nonce(secret_key, nonce, &z),
+    );
+
+    if leaks {
+        std::process::exit(1);
+    }
+}
@@ -2,12 +2,11 @@
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
-use pasta_curves::arithmetic::CurveExt;
-use pasta_curves::{pallas, vesta};
+use secp256k1forhalo2::arithmetic::CurveExt;
+use secp256k1forhalo2::Secp256k1;
 
 fn criterion_benchmark(c: &mut Criterion) {
-    point_bench::<pallas::Point>(c, "Pallas");
-    point_bench::<vesta::Point>(c, "Vesta");
+    point_bench::<Secp256k1>(c, "secp256k1");
 }
 
 fn point_bench<C: CurveExt>(c: &mut Criterion, name: &str) {
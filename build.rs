@@ -0,0 +1,255 @@
+//! Generates the wNAF tables of the generator $G$ and of $\lambda G$ for the
+//! `precomputed-tables` feature, so that they are neither computed at runtime
+//! nor checked into the source tree.
+//!
+//! The window width is read from `SECP256K1_GENERATOR_WNAF_WIDTH`, between 2
+//! and 8, and defaults to 8. The arithmetic below is a small affine
+//! implementation over the base field that shares no code with the crate;
+//! the `test_generated_tables` test checks its output against `Wnaf::new`.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// $2^{256} - p$.
+const P_COMPLEMENT: u64 = 0x1_0000_03d1;
+
+/// The base field modulus $p = 2^{256} - 2^{32} - 977$.
+const P: [u64; 4] = [
+    0xffff_fffe_ffff_fc2f,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+
+/// $p - 2$, the exponent of inversion.
+const P_MINUS_2: [u64; 4] = [
+    0xffff_fffe_ffff_fc2d,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+
+/// The cube root of unity $\beta$ = `Fp::BETA`.
+const BETA: [u64; 4] = [
+    0xc139_6c28_7195_01ee,
+    0x9cf0_4975_12f5_8995,
+    0x6e64_479e_ac34_34e9,
+    0x7ae9_6a2b_657c_0710,
+];
+
+const GENERATOR_X: [u64; 4] = [
+    0x59f2_815b_16f8_1798,
+    0x029b_fcdb_2dce_28d9,
+    0x55a0_6295_ce87_0b07,
+    0x79be_667e_f9dc_bbac,
+];
+
+const GENERATOR_Y: [u64; 4] = [
+    0x9c47_d08f_fb10_d4b8,
+    0xfd17_b448_a685_5419,
+    0x5da4_fbfc_0e11_08a8,
+    0x483a_da77_26a3_c465,
+];
+
+type Fe = [u64; 4];
+
+/// Returns `a + c`, where `c` is less than $2^{64}$, and the carry out.
+fn add_small(a: &Fe, c: u64) -> (Fe, u64) {
+    let mut r = [0; 4];
+    let mut carry = c as u128;
+    for i in 0..4 {
+        let t = a[i] as u128 + carry;
+        r[i] = t as u64;
+        carry = t >> 64;
+    }
+    (r, carry as u64)
+}
+
+/// Subtracts $p$ once if `a` is not less than $p$.
+fn reduce_once(a: Fe) -> Fe {
+    if a.iter().rev().cmp(P.iter().rev()) == std::cmp::Ordering::Less {
+        a
+    } else {
+        // a - p = a + (2^256 - p) modulo 2^256.
+        add_small(&a, P_COMPLEMENT).0
+    }
+}
+
+fn add(a: &Fe, b: &Fe) -> Fe {
+    let mut r = [0; 4];
+    let mut carry = 0u128;
+    for i in 0..4 {
+        let t = a[i] as u128 + b[i] as u128 + carry;
+        r[i] = t as u64;
+        carry = t >> 64;
+    }
+    if carry != 0 {
+        // 2^256 = 2^256 - p modulo p, and a + b - p < p.
+        r = add_small(&r, P_COMPLEMENT).0;
+    }
+    reduce_once(r)
+}
+
+fn neg(a: &Fe) -> Fe {
+    if a.iter().all(|&l| l == 0) {
+        return *a;
+    }
+    let mut r = [0; 4];
+    let mut borrow = 0i128;
+    for i in 0..4 {
+        let t = P[i] as i128 - a[i] as i128 + borrow;
+        r[i] = t as u64;
+        borrow = t >> 64;
+    }
+    r
+}
+
+fn sub(a: &Fe, b: &Fe) -> Fe {
+    add(a, &neg(b))
+}
+
+fn mul(a: &Fe, b: &Fe) -> Fe {
+    let mut wide = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let t = wide[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+            wide[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+
+    // Fold the high half onto the low half twice, since 2^256 = 2^256 - p
+    // modulo p.
+    let mut r = [0; 4];
+    let mut carry = 0u128;
+    for i in 0..4 {
+        let t = wide[i] as u128 + wide[i + 4] as u128 * P_COMPLEMENT as u128 + carry;
+        r[i] = t as u64;
+        carry = t >> 64;
+    }
+    let mut carry = carry * P_COMPLEMENT as u128;
+    for limb in r.iter_mut() {
+        let t = *limb as u128 + carry;
+        *limb = t as u64;
+        carry = t >> 64;
+    }
+    if carry != 0 {
+        // r is now tiny, so this cannot carry out again.
+        r = add_small(&r, P_COMPLEMENT).0;
+    }
+    reduce_once(r)
+}
+
+fn invert(a: &Fe) -> Fe {
+    let mut r = [1, 0, 0, 0];
+    for limb in P_MINUS_2.iter().rev() {
+        for bit in (0..64).rev() {
+            r = mul(&r, &r);
+            if (limb >> bit) & 1 == 1 {
+                r = mul(&r, a);
+            }
+        }
+    }
+    r
+}
+
+/// Adds two affine points with distinct x-coordinates.
+fn add_points((x1, y1): &(Fe, Fe), (x2, y2): &(Fe, Fe)) -> (Fe, Fe) {
+    let lambda = mul(&sub(y2, y1), &invert(&sub(x2, x1)));
+    let x3 = sub(&sub(&mul(&lambda, &lambda), x1), x2);
+    let y3 = sub(&mul(&lambda, &sub(x1, &x3)), y1);
+    (x3, y3)
+}
+
+/// Doubles an affine point with nonzero y-coordinate.
+fn double_point((x, y): &(Fe, Fe)) -> (Fe, Fe) {
+    let xx = mul(x, x);
+    let lambda = mul(&add(&add(&xx, &xx), &xx), &invert(&add(y, y)));
+    let x3 = sub(&mul(&lambda, &lambda), &add(x, x));
+    let y3 = sub(&mul(&lambda, &sub(x, &x3)), y);
+    (x3, y3)
+}
+
+fn limbs(a: &Fe) -> String {
+    format!(
+        "[0x{:016x}, 0x{:016x}, 0x{:016x}, 0x{:016x}]",
+        a[0], a[1], a[2], a[3]
+    )
+}
+
+fn write_table(out: &mut String, name: &str, points: &[(Fe, Fe)]) {
+    writeln!(
+        out,
+        "pub(super) const {}: [Secp256k1Affine; {}] = [",
+        name,
+        points.len()
+    )
+    .unwrap();
+    for (x, y) in points {
+        writeln!(
+            out,
+            "    Secp256k1Affine::from_raw_unchecked({}, {}),",
+            limbs(x),
+            limbs(y)
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=SECP256K1_GENERATOR_WNAF_WIDTH");
+    if env::var_os("CARGO_FEATURE_PRECOMPUTED_TABLES").is_none() {
+        return;
+    }
+
+    let width = match env::var("SECP256K1_GENERATOR_WNAF_WIDTH") {
+        Ok(width) => width
+            .parse::<usize>()
+            .ok()
+            .filter(|width| (2..=8).contains(width))
+            .expect("SECP256K1_GENERATOR_WNAF_WIDTH must be between 2 and 8"),
+        Err(_) => 8,
+    };
+    let size = 1 << (width - 2);
+
+    let generator = (GENERATOR_X, GENERATOR_Y);
+    let double = double_point(&generator);
+    let mut odd_multiples = vec![generator];
+    for i in 1..size {
+        let next = add_points(&odd_multiples[i - 1], &double);
+        odd_multiples.push(next);
+    }
+    let endo_odd_multiples: Vec<_> = odd_multiples
+        .iter()
+        .map(|(x, y)| (mul(x, &BETA), *y))
+        .collect();
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Generated by build.rs for a window width of {}.",
+        width
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub(super) const GENERATOR_WNAF_TABLE_SIZE: usize = {};",
+        size
+    )
+    .unwrap();
+    write_table(&mut out, "GENERATOR_ODD_MULTIPLES", &odd_multiples);
+    write_table(
+        &mut out,
+        "GENERATOR_ENDO_ODD_MULTIPLES",
+        &endo_odd_multiples,
+    );
+
+    let path = Path::new(&env::var_os("OUT_DIR").unwrap()).join("generator_wnaf.rs");
+    fs::write(path, out).unwrap();
+}
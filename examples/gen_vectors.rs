@@ -0,0 +1,145 @@
+//! Emits JSON test vectors for field and point operations.
+//!
+//! ```text
+//! cargo run --example gen_vectors -- [seed] [count]
+//! ```
+//!
+//! `seed` (default 0) seeds an `XorShiftRng` via `SeedableRng::seed_from_u64`
+//! and `count` (default 16) is the number of vectors per section, so the same
+//! arguments always produce the same output.
+//!
+//! Schema. Field elements are the 32-byte little-endian canonical encoding
+//! (`PrimeField::to_repr`) and points are the 64-byte `GroupEncoding` of the
+//! affine point, `x || y` each little-endian with the identity encoded as all
+//! zeros, all as lowercase hex strings.
+//!
+//! ```text
+//! {
+//!   "seed": <u64>,
+//!   "fp": [ { "a", "b", "add", "sub", "mul", "neg", "square", "invert" }, ... ],
+//!   "fq": [ { "a", "b", "add", "sub", "mul", "neg", "square", "invert" }, ... ],
+//!   "point": [ { "p", "q", "k", "add", "sub", "double", "neg", "mul" }, ... ],
+//!   "ecdsa": [ { "d", "public_key", "k", "z", "r", "s", "recovery_id" }, ... ]
+//! }
+//! ```
+//!
+//! In the `point` section `k` is an `Fq` element and `mul` is `p * k`.
+//!
+//! In the `ecdsa` section `d` is the secret key, `k` the nonce and `z` the
+//! message hash reduced modulo $q$, all `Fq` elements, and `(r, s)` is the
+//! signature from `hazmat::sign_prehashed_with_nonce`. `s` is not normalized
+//! to low-S. `recovery_id` is the recovery id byte, from 0 to 3.
+//!
+//! There is no Schnorr section. The crate has no BIP-340 signing to generate
+//! vectors from: its challenge is a SHA-256 tagged hash, and the crate does
+//! not depend on SHA-256. The `taproot` module only lifts x-only keys and
+//! checks tweaks.
+use std::env;
+
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use ff::{Field, PrimeField};
+use group::{Curve, Group, GroupEncoding};
+use secp256k1forhalo2::{hazmat, Fp, Fq, Secp256k1};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn field_vectors<F: PrimeField>(rng: &mut XorShiftRng, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let a = F::random(&mut *rng);
+            let b = F::random(&mut *rng);
+            let f = |x: F| hex(x.to_repr().as_ref());
+            format!(
+                "{{\"a\": \"{}\", \"b\": \"{}\", \"add\": \"{}\", \"sub\": \"{}\", \"mul\": \"{}\", \"neg\": \"{}\", \"square\": \"{}\", \"invert\": \"{}\"}}",
+                f(a),
+                f(b),
+                f(a + b),
+                f(a - b),
+                f(a * b),
+                f(-a),
+                f(a.square()),
+                f(a.invert().unwrap()),
+            )
+        })
+        .collect()
+}
+
+fn point_vectors(rng: &mut XorShiftRng, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let p = Secp256k1::random(&mut *rng);
+            let q = Secp256k1::random(&mut *rng);
+            let k = Fq::random(&mut *rng);
+            let e = |x: Secp256k1| hex(x.to_affine().to_bytes().as_ref());
+            format!(
+                "{{\"p\": \"{}\", \"q\": \"{}\", \"k\": \"{}\", \"add\": \"{}\", \"sub\": \"{}\", \"double\": \"{}\", \"neg\": \"{}\", \"mul\": \"{}\"}}",
+                e(p),
+                e(q),
+                hex(&k.to_repr()),
+                e(p + q),
+                e(p - q),
+                e(p.double()),
+                e(-p),
+                e(p * k),
+            )
+        })
+        .collect()
+}
+
+fn ecdsa_vectors(rng: &mut XorShiftRng, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let secret_key = Fq::random(&mut *rng);
+            let nonce = Fq::random(&mut *rng);
+            let z = Fq::random(&mut *rng);
+            let (r, s, recovery_id) =
+                hazmat::sign_prehashed_with_nonce(&secret_key, &nonce, &z).expect("r and s are nonzero");
+            let f = |x: Fq| hex(x.to_repr().as_ref());
+            format!(
+                "{{\"d\": \"{}\", \"public_key\": \"{}\", \"k\": \"{}\", \"z\": \"{}\", \"r\": \"{}\", \"s\": \"{}\", \"recovery_id\": {}}}",
+                f(secret_key),
+                hex((Secp256k1::generator() * secret_key).to_affine().to_bytes().as_ref()),
+                f(nonce),
+                f(z),
+                f(r),
+                f(s),
+                recovery_id.to_byte(),
+            )
+        })
+        .collect()
+}
+
+fn section(name: &str, vectors: &[String]) -> String {
+    format!("  \"{}\": [\n    {}\n  ]", name, vectors.join(",\n    "))
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let seed: u64 = args
+        .next()
+        .map(|s| s.parse().expect("seed must be a u64"))
+        .unwrap_or(0);
+    let count: usize = args
+        .next()
+        .map(|s| s.parse().expect("count must be a usize"))
+        .unwrap_or(16);
+
+    let mut rng = XorShiftRng::seed_from_u64(seed);
+
+    let fp = field_vectors::<Fp>(&mut rng, count);
+    let fq = field_vectors::<Fq>(&mut rng, count);
+    let point = point_vectors(&mut rng, count);
+    let ecdsa = ecdsa_vectors(&mut rng, count);
+
+    println!("{{");
+    println!("  \"seed\": {},", seed);
+    println!("{},", section("fp", &fp));
+    println!("{},", section("fq", &fq));
+    println!("{},", section("point", &point));
+    println!("{}", section("ecdsa", &ecdsa));
+    println!("}}");
+}
@@ -3,19 +3,34 @@
 //!
 //! This module is temporary, and the extension traits defined here are expected to be
 //! upstreamed into the `ff` and `group` crates after some refactoring.
+//!
+//! The limb-level helpers [`adc`], [`sbb`], [`mac`], [`montgomery_reduce_wide`]
+//! and [`montgomery_reduce_pseudo_mersenne`] are exposed as building blocks for
+//! crates implementing their own arithmetic.
+//! They do not validate their inputs, and their signatures are not covered by
+//! semver.
+//!
+//! With the `pasta` feature enabled the extension traits are re-exported from
+//! `pasta_curves` instead, so that the types of this crate can be used with code
+//! written against those traits.
 
+#[cfg(not(feature = "pasta"))]
+mod curves;
 mod fields;
 
-pub(crate) use fields::*;
-
-#[cfg(feature = "std")]
+#[cfg(not(feature = "pasta"))]
+pub use curves::*;
 pub use fields::*;
-use pasta_curves::arithmetic::FieldExt;
+
+#[cfg(feature = "pasta")]
+pub use pasta_curves::arithmetic::{
+    Coordinates, CurveAffine, CurveExt, FieldExt, Group, SqrtRatio,
+};
 
 /// This represents an element of a group with basic operations that can be
 /// performed. This allows an FFT implementation (for example) to operate
 /// generically over either a field or elliptic curve group.
-#[cfg(feature = "std")]
+#[cfg(not(feature = "pasta"))]
 pub trait Group: Copy + Clone + Send + Sync + 'static {
     /// The group is assumed to be of prime order $p$. `Scalar` is the
     /// associated scalar field of size $p$.
@@ -3,37 +3,498 @@
 
 use core::mem::size_of;
 
+use ff::Field;
 use static_assertions::const_assert;
-use subtle::Choice;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-#[cfg(not(feature = "std"))]
-use subtle::CtOption;
-
-#[cfg(feature = "std")]
+#[cfg(not(feature = "pasta"))]
 use super::Group;
 
-#[cfg(feature = "std")]
-use std::{assert, boxed::Box, convert::TryInto, marker::PhantomData, vec::Vec};
-
 const_assert!(size_of::<usize>() >= 4);
 
 /// Compute a + b + carry, returning the result and the new carry over.
 #[inline(always)]
-pub(crate) const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+pub const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
     let ret = (a as u128) + (b as u128) + (carry as u128);
     (ret as u64, (ret >> 64) as u64)
 }
 
 /// Compute a - (b + borrow), returning the result and the new borrow.
 #[inline(always)]
-pub(crate) const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+pub const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
     let ret = (a as u128).wrapping_sub((b as u128) + ((borrow >> 63) as u128));
     (ret as u64, (ret >> 64) as u64)
 }
 
 /// Compute a + (b * c) + carry, returning the result and the new carry over.
 #[inline(always)]
-pub(crate) const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+pub const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
     let ret = (a as u128) + ((b as u128) * (c as u128)) + (carry as u128);
     (ret as u64, (ret >> 64) as u64)
 }
+
+/// Parses the big-endian hex integer `hex`, with an optional `0x` prefix and
+/// between 1 and 64 digits, into little-endian limbs.
+///
+/// Panics on invalid input by indexing out of bounds, which is the only way
+/// a `const fn` can fail; in a const context this is a compile-time error.
+pub(crate) const fn hex_to_limbs(hex: &str) -> [u64; 4] {
+    const INVALID: [u64; 0] = [];
+
+    let bytes = hex.as_bytes();
+    let start = if bytes.len() >= 2 && bytes[0] == b'0' && bytes[1] == b'x' {
+        2
+    } else {
+        0
+    };
+    let num_digits = bytes.len() - start;
+    if num_digits == 0 || num_digits > 64 {
+        return [INVALID[num_digits]; 4];
+    }
+
+    let mut limbs = [0u64; 4];
+    let mut i = 0;
+    while i < num_digits {
+        let c = bytes[bytes.len() - 1 - i];
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return [INVALID[c as usize]; 4],
+        };
+        limbs[i / 16] |= (digit as u64) << (4 * (i % 16));
+        i += 1;
+    }
+    limbs
+}
+
+/// This trait is a common interface for dealing with elements of a finite
+/// field.
+#[cfg(not(feature = "pasta"))]
+pub trait FieldExt:
+    SqrtRatio + From<bool> + Ord + ConstantTimeEq + core::ops::Neg<Output = Self> + Group<Scalar = Self>
+{
+    /// Modulus of the field written as a string for display purposes
+    const MODULUS: &'static str;
+
+    /// Inverse of `PrimeField::root_of_unity()`
+    const ROOT_OF_UNITY_INV: Self;
+
+    /// Generator of the $t-order$ multiplicative subgroup
+    const DELTA: Self;
+
+    /// Inverse of $2$ in the field.
+    const TWO_INV: Self;
+
+    /// Element of multiplicative order $3$.
+    const ZETA: Self;
+
+    /// Obtains a field element congruent to the integer `v`.
+    fn from_u128(v: u128) -> Self;
+
+    /// Obtains a field element that is congruent to the provided little endian
+    /// byte representation of an integer.
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self;
+
+    /// Returns a random element of the field using the operating system's
+    /// randomness.
+    #[cfg(feature = "std")]
+    fn rand() -> Self {
+        Self::random(rand::rngs::OsRng)
+    }
+
+    /// Gets the lower 128 bits of this field element when expressed
+    /// canonically.
+    fn get_lower_128(&self) -> u128;
+
+    /// Exponentiates `self` by `by`, where `by` is a little-endian order
+    /// integer exponent.
+    fn pow(&self, by: &[u64; 4]) -> Self {
+        let mut res = Self::one();
+        for e in by.iter().rev() {
+            for i in (0..64).rev() {
+                res = res.square();
+                let mut tmp = res;
+                tmp *= self;
+                res.conditional_assign(&tmp, (((*e >> i) & 0x1) as u8).into());
+            }
+        }
+        res
+    }
+}
+
+/// A trait that exposes additional operations related to calculating square
+/// roots of prime-order finite fields.
+#[cfg(not(feature = "pasta"))]
+pub trait SqrtRatio: ff::PrimeField {
+    /// The value $(T-1)/2$ such that $2^S \cdot T = p - 1$ with $T$ odd.
+    const T_MINUS1_OVER2: [u64; 4];
+
+    /// Raise this field element to the power [`Self::T_MINUS1_OVER2`].
+    fn pow_by_t_minus1_over2(&self) -> Self;
+
+    /// Gets the lower 32 bits of this field element when expressed
+    /// canonically.
+    fn get_lower_32(&self) -> u32;
+
+    /// Computes:
+    ///
+    /// - $(\textsf{true}, \sqrt{\textsf{num}/\textsf{div}})$, if $\textsf{num}$ and
+    ///   $\textsf{div}$ are nonzero and $\textsf{num}/\textsf{div}$ is a square in the
+    ///   field;
+    /// - $(\textsf{true}, 0)$, if $\textsf{num}$ is zero;
+    /// - $(\textsf{false}, 0)$, if $\textsf{num}$ is nonzero and $\textsf{div}$ is zero;
+    /// - $(\textsf{false}, \sqrt{G_S \cdot \textsf{num}/\textsf{div}})$, if
+    ///   $\textsf{num}$ and $\textsf{div}$ are nonzero and $\textsf{num}/\textsf{div}$ is
+    ///   a nonsquare in the field;
+    ///
+    /// where $G_S$ is a non-square.
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self);
+
+    /// Equivalent to `Self::sqrt_ratio(self, one())`.
+    fn sqrt_alt(&self) -> (Choice, Self) {
+        Self::sqrt_ratio(self, &Self::one())
+    }
+}
+
+/// The Legendre symbol of a field element, which tells whether it is zero, a
+/// nonzero square or a non-square.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegendreSymbol {
+    /// The element is zero.
+    Zero,
+    /// The element is a nonzero square.
+    QuadraticResidue,
+    /// The element is not a square.
+    QuadraticNonResidue,
+}
+
+/// Computes the square root of `f` with the constant-time Tonelli-Shanks
+/// algorithm, given `tm1d2` $= (t - 1) / 2$ where $p - 1 = 2^S \cdot t$ with
+/// $t$ odd.
+pub fn sqrt_tonelli_shanks<F: ff::PrimeField, S: AsRef<[u64]>>(f: &F, tm1d2: S) -> CtOption<F> {
+    // w = self^((t - 1) // 2)
+    sqrt_tonelli_shanks_with_w(f, f.pow_vartime(tm1d2))
+}
+
+/// Computes the square root of `f` with the constant-time Tonelli-Shanks
+/// algorithm, given `w` $= f^{(t - 1) / 2}$ where $p - 1 = 2^S \cdot t$ with
+/// $t$ odd, such as computed by an addition chain in
+/// `SqrtRatio::pow_by_t_minus1_over2`. This needs neither an allocator nor
+/// precomputed tables.
+#[allow(clippy::many_single_char_names)]
+pub fn sqrt_tonelli_shanks_with_w<F: ff::PrimeField>(f: &F, w: F) -> CtOption<F> {
+    let mut v = F::S;
+    let mut x = w * f;
+    let mut b = x * w;
+
+    // Initialize z as the 2^S root of unity.
+    let mut z = F::root_of_unity();
+
+    for max_v in (1..=F::S).rev() {
+        let mut k = 1;
+        let mut tmp = b.square();
+        let mut j_less_than_v: Choice = 1.into();
+
+        for j in 2..max_v {
+            let tmp_is_one = tmp.ct_eq(&F::one());
+            let squared = F::conditional_select(&tmp, &z, tmp_is_one).square();
+            tmp = F::conditional_select(&squared, &tmp, tmp_is_one);
+            let new_z = F::conditional_select(&z, &squared, tmp_is_one);
+            j_less_than_v &= !j.ct_eq(&v);
+            k = u32::conditional_select(&j, &k, tmp_is_one);
+            z = F::conditional_select(&z, &new_z, j_less_than_v);
+        }
+
+        let result = x * z;
+        x = F::conditional_select(&result, &x, b.ct_eq(&F::one()));
+        z = z.square();
+        b *= z;
+        v = k;
+    }
+
+    CtOption::new(x, (x * x).ct_eq(f))
+}
+
+/// Computes $r \cdot 2^{-256} \bmod m$ for the 512-bit little-endian integer
+/// `r`, where `modulus` holds the little-endian limbs of $m$ and
+/// `inv` $= -m^{-1} \bmod 2^{64}$. The result is fully reduced provided that
+/// $r < m \cdot 2^{256}$.
+#[inline(always)]
+pub const fn montgomery_reduce_wide(r: &[u64; 8], modulus: &[u64; 4], inv: u64) -> [u64; 4] {
+    // The Montgomery reduction here is based on Algorithm 14.32 in
+    // Handbook of Applied Cryptography
+    // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
+
+    let k = r[0].wrapping_mul(inv);
+    let (_, carry) = mac(r[0], k, modulus[0], 0);
+    let (r1, carry) = mac(r[1], k, modulus[1], carry);
+    let (r2, carry) = mac(r[2], k, modulus[2], carry);
+    let (r3, carry) = mac(r[3], k, modulus[3], carry);
+    let (r4, carry2) = adc(r[4], 0, carry);
+
+    let k = r1.wrapping_mul(inv);
+    let (_, carry) = mac(r1, k, modulus[0], 0);
+    let (r2, carry) = mac(r2, k, modulus[1], carry);
+    let (r3, carry) = mac(r3, k, modulus[2], carry);
+    let (r4, carry) = mac(r4, k, modulus[3], carry);
+    let (r5, carry2) = adc(r[5], carry2, carry);
+
+    let k = r2.wrapping_mul(inv);
+    let (_, carry) = mac(r2, k, modulus[0], 0);
+    let (r3, carry) = mac(r3, k, modulus[1], carry);
+    let (r4, carry) = mac(r4, k, modulus[2], carry);
+    let (r5, carry) = mac(r5, k, modulus[3], carry);
+    let (r6, carry2) = adc(r[6], carry2, carry);
+
+    let k = r3.wrapping_mul(inv);
+    let (_, carry) = mac(r3, k, modulus[0], 0);
+    let (r4, carry) = mac(r4, k, modulus[1], carry);
+    let (r5, carry) = mac(r5, k, modulus[2], carry);
+    let (r6, carry) = mac(r6, k, modulus[3], carry);
+    let (r7, carry2) = adc(r[7], carry2, carry);
+
+    // Result may be within MODULUS of the correct value
+    let (d0, borrow) = sbb(r4, modulus[0], 0);
+    let (d1, borrow) = sbb(r5, modulus[1], borrow);
+    let (d2, borrow) = sbb(r6, modulus[2], borrow);
+    let (d3, borrow) = sbb(r7, modulus[3], borrow);
+    let (_, borrow) = sbb(carry2, 0, borrow);
+
+    let (d0, carry) = adc(d0, modulus[0] & borrow, 0);
+    let (d1, carry) = adc(d1, modulus[1] & borrow, carry);
+    let (d2, carry) = adc(d2, modulus[2] & borrow, carry);
+    let (d3, _) = adc(d3, modulus[3] & borrow, carry);
+
+    [d0, d1, d2, d3]
+}
+
+/// Computes $r \cdot 2^{-256} \bmod m$ as [`montgomery_reduce_wide`] does, for
+/// a pseudo-Mersenne modulus $m = 2^{256} - c$ with $c < 2^{64}$, that is,
+/// whose three upper limbs are all ones.
+///
+/// The reduction adds $K m = K 2^{256} - K c$ for the 256-bit $K$ whose limbs
+/// $k_i$ clear the low half of $r$, so the result is
+/// $r_{hi} + K - (K c - r_{lo}) / 2^{256}$. Each $k_i$ is found from a single
+/// limb as $m \equiv -c \pmod{2^{64}}$, and subtracting $k_i c$ only touches
+/// two limbs, of which the lower becomes zero. This takes four $64 \times 64$
+/// multiplications by `inv` and four by $c$, instead of four and sixteen.
+#[inline(always)]
+pub const fn montgomery_reduce_pseudo_mersenne(
+    r: &[u64; 8],
+    modulus: &[u64; 4],
+    inv: u64,
+) -> [u64; 4] {
+    let c = modulus[0].wrapping_neg();
+
+    // `s` is the amount still to be subtracted at the next limb: the high
+    // half of k_i c plus the borrow out of the current limb. As the low half
+    // of k_i c equals the current limb, the subtraction there is exact.
+    let k0 = r[0].wrapping_mul(inv);
+    let s = (((k0 as u128) * (c as u128)) >> 64) as u64;
+
+    let (l1, borrow) = sbb(r[1], s, 0);
+    let k1 = l1.wrapping_mul(inv);
+    let s = (((k1 as u128) * (c as u128)) >> 64) as u64 + (borrow >> 63);
+
+    let (l2, borrow) = sbb(r[2], s, 0);
+    let k2 = l2.wrapping_mul(inv);
+    let s = (((k2 as u128) * (c as u128)) >> 64) as u64 + (borrow >> 63);
+
+    let (l3, borrow) = sbb(r[3], s, 0);
+    let k3 = l3.wrapping_mul(inv);
+    let s = (((k3 as u128) * (c as u128)) >> 64) as u64 + (borrow >> 63);
+
+    // r_hi + K - s, which is (r + K m) / 2^256 and so is nonnegative: the
+    // final borrow is always zero.
+    let (d0, carry) = adc(r[4], k0, 0);
+    let (d1, carry) = adc(r[5], k1, carry);
+    let (d2, carry) = adc(r[6], k2, carry);
+    let (d3, d4) = adc(r[7], k3, carry);
+
+    let (d0, borrow) = sbb(d0, s, 0);
+    let (d1, borrow) = sbb(d1, 0, borrow);
+    let (d2, borrow) = sbb(d2, 0, borrow);
+    let (d3, borrow) = sbb(d3, 0, borrow);
+    let (d4, _) = sbb(d4, 0, borrow);
+
+    // Result may be within MODULUS of the correct value
+    let (d0, borrow) = sbb(d0, modulus[0], 0);
+    let (d1, borrow) = sbb(d1, modulus[1], borrow);
+    let (d2, borrow) = sbb(d2, modulus[2], borrow);
+    let (d3, borrow) = sbb(d3, modulus[3], borrow);
+    let (_, borrow) = sbb(d4, 0, borrow);
+
+    let (d0, carry) = adc(d0, modulus[0] & borrow, 0);
+    let (d1, carry) = adc(d1, modulus[1] & borrow, carry);
+    let (d2, carry) = adc(d2, modulus[2] & borrow, carry);
+    let (d3, _) = adc(d3, modulus[3] & borrow, carry);
+
+    [d0, d1, d2, d3]
+}
+
+/// Computes $a^{-1} \bmod m$ for a nonzero `a` $< m$ and an odd `m`, both
+/// little-endian, with the binary extended Euclidean algorithm. This is not
+/// constant time.
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn invert_vartime_limbs(a: &[u64; 4], m: &[u64; 4]) -> [u64; 4] {
+    fn is_one(a: &[u64; 4]) -> bool {
+        *a == [1, 0, 0, 0]
+    }
+
+    /// Halves `a` modulo `m`, for `a < m`.
+    fn halve(a: &mut [u64; 4], m: &[u64; 4]) {
+        let mut carry = 0;
+        if a[0] & 1 == 1 {
+            for (a, m) in a.iter_mut().zip(m.iter()) {
+                let (r, c) = adc(*a, *m, carry);
+                *a = r;
+                carry = c;
+            }
+        }
+        for i in 0..4 {
+            let hi = if i < 3 { a[i + 1] } else { carry };
+            a[i] = (a[i] >> 1) | (hi << 63);
+        }
+    }
+
+    /// Sets `a` to `a - b`, returning the borrow.
+    fn sub(a: &mut [u64; 4], b: &[u64; 4]) -> u64 {
+        let mut borrow = 0;
+        for (a, b) in a.iter_mut().zip(b.iter()) {
+            let (r, br) = sbb(*a, *b, borrow);
+            *a = r;
+            borrow = br;
+        }
+        borrow
+    }
+
+    /// Sets `a` to `a - b mod m`, for `a, b < m`.
+    fn sub_mod(a: &mut [u64; 4], b: &[u64; 4], m: &[u64; 4]) {
+        if sub(a, b) != 0 {
+            let mut carry = 0;
+            for (a, m) in a.iter_mut().zip(m.iter()) {
+                let (r, c) = adc(*a, *m, carry);
+                *a = r;
+                carry = c;
+            }
+        }
+    }
+
+    let (mut u, mut v) = (*a, *m);
+    let (mut x1, mut x2) = ([1, 0, 0, 0], [0u64; 4]);
+    while !is_one(&u) && !is_one(&v) {
+        while u[0] & 1 == 0 {
+            halve(&mut u, &[0; 4]);
+            halve(&mut x1, m);
+        }
+        while v[0] & 1 == 0 {
+            halve(&mut v, &[0; 4]);
+            halve(&mut x2, m);
+        }
+        if u.iter().rev().cmp(v.iter().rev()) != core::cmp::Ordering::Less {
+            sub(&mut u, &v);
+            sub_mod(&mut x1, &x2, m);
+        } else {
+            sub(&mut v, &u);
+            sub_mod(&mut x2, &x1, m);
+        }
+    }
+
+    if is_one(&u) {
+        x1
+    } else {
+        x2
+    }
+}
+
+/// Returns the length of the chunks the slice operations split a slice of
+/// length `len` into, one per thread.
+#[cfg(feature = "multicore")]
+fn chunk_size(len: usize) -> usize {
+    let threads = rayon::current_num_threads();
+    core::cmp::max(1, (len + threads - 1) / threads)
+}
+
+/// Applies `f` to each element of `a` and the element of `b` at the same
+/// index, splitting the slices into one chunk per thread with the
+/// `multicore` feature.
+fn zip_with<F: Field>(a: &mut [F], b: &[F], f: impl Fn(&mut F, &F) + Send + Sync) {
+    assert_eq!(a.len(), b.len());
+
+    #[cfg(feature = "multicore")]
+    {
+        use rayon::prelude::*;
+
+        let chunk = chunk_size(a.len());
+        a.par_chunks_mut(chunk)
+            .zip(b.par_chunks(chunk))
+            .for_each(|(a, b)| {
+                for (a, b) in a.iter_mut().zip(b.iter()) {
+                    f(a, b);
+                }
+            });
+    }
+
+    #[cfg(not(feature = "multicore"))]
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        f(a, b);
+    }
+}
+
+/// Adds `b` to `a` element-wise, storing the result in `a`.
+///
+/// Panics if the slices have different lengths.
+pub fn add_slices<F: Field>(a: &mut [F], b: &[F]) {
+    zip_with(a, b, |a, b| *a += b);
+}
+
+/// Multiplies `a` by `b` element-wise, storing the result in `a`.
+///
+/// Panics if the slices have different lengths.
+pub fn mul_slices<F: Field>(a: &mut [F], b: &[F]) {
+    zip_with(a, b, |a, b| *a *= b);
+}
+
+/// Multiplies every element of `a` by `by`.
+pub fn scale_slice<F: Field>(a: &mut [F], by: &F) {
+    #[cfg(feature = "multicore")]
+    {
+        use rayon::prelude::*;
+
+        let chunk = chunk_size(a.len());
+        a.par_chunks_mut(chunk).for_each(|a| {
+            for a in a.iter_mut() {
+                *a *= by;
+            }
+        });
+    }
+
+    #[cfg(not(feature = "multicore"))]
+    for a in a.iter_mut() {
+        *a *= by;
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+#[test]
+fn test_slice_ops() {
+    use crate::Fq;
+    use std::vec::Vec;
+
+    let a: Vec<Fq> = (0..10).map(Fq::from).collect();
+    let b: Vec<Fq> = (10..20).map(Fq::from).collect();
+
+    let mut sum = a.clone();
+    add_slices(&mut sum, &b);
+    let mut prod = a.clone();
+    mul_slices(&mut prod, &b);
+    let mut scaled = a.clone();
+    scale_slice(&mut scaled, &Fq::from(3));
+
+    for i in 0..10 {
+        assert_eq!(sum[i], a[i] + b[i]);
+        assert_eq!(prod[i], a[i] * b[i]);
+        assert_eq!(scaled[i], a[i] * Fq::from(3));
+    }
+}
@@ -9,6 +9,7 @@ use core::ops::{Add, Mul, Neg, Sub};
 #[cfg(feature = "std")]
 use std::boxed::Box;
 
+use core::convert::TryInto;
 use ff::{Field, PrimeField};
 use group::{
     cofactor::{CofactorCurve, CofactorGroup},
@@ -16,19 +17,20 @@ use group::{
     Curve as _, Group as _, GroupEncoding,
 };
 use rand::RngCore;
-use std::convert::TryInto;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 use super::{Fp, Fq};
 
+use crate::arithmetic::{Coordinates, CurveAffine, CurveExt, Group};
 #[cfg(feature = "std")]
-use pasta_curves::arithmetic::{Coordinates, CurveAffine, CurveExt, Group};
+use crate::serde::SerdeObject;
 
 macro_rules! new_curve_impl {
     (($($privacy:tt)*), $name:ident, $name_affine:ident, $base:ident, $scalar:ident,
-     $curve_id:literal, $a_raw:expr, $b_raw:expr, $curve_type:ident) => {
+     $curve_id:literal, $a_raw:expr, $b_raw:expr, $gx_raw:expr, $gy_raw:expr,
+     $curve_type:ident) => {
         /// Represents a point in the projective coordinate space.
-        #[derive(Copy, Clone, Debug)]
+        #[derive(Copy, Clone)]
         $($privacy)* struct $name {
             x: $base,
             y: $base,
@@ -36,6 +38,24 @@ macro_rules! new_curve_impl {
         }
 
         impl $name {
+            /// The generator of the group, in affine coordinates with `z = 1`.
+            pub const GENERATOR: Self = $name {
+                x: $name_affine::GENERATOR.x,
+                y: $name_affine::GENERATOR.y,
+                z: $base::one(),
+            };
+
+            /// Returns the generator of the group.
+            pub const fn generator() -> Self {
+                Self::GENERATOR
+            }
+
+            /// Returns whether or not this point is the identity, in constant
+            /// time. The identity is any point with `z = 0`.
+            pub fn is_identity(&self) -> Choice {
+                self.z.is_zero()
+            }
+
             const fn curve_constant_a() -> $base {
                 $base::from_raw($a_raw)
             }
@@ -46,46 +66,105 @@ macro_rules! new_curve_impl {
         }
 
         /// Represents a point in the affine coordinate space (or the point at
-        /// infinity).
+        /// infinity, encoded as `(0, 0)`, which is not on the curve since
+        /// `b != 0`).
         #[derive(Copy, Clone)]
         $($privacy)* struct $name_affine {
             x: $base,
             y: $base,
-            infinity: Choice,
+        }
+
+        impl $name_affine {
+            /// The generator of the group.
+            pub const GENERATOR: Self = $name_affine {
+                x: $base::from_raw($gx_raw),
+                y: $base::from_raw($gy_raw),
+            };
+
+            /// Returns the generator of the group.
+            pub const fn generator() -> Self {
+                Self::GENERATOR
+            }
+
+            /// Returns whether or not this point is the identity, in constant
+            /// time. The identity is encoded as `(0, 0)`.
+            pub fn is_identity(&self) -> Choice {
+                self.x.is_zero() & self.y.is_zero()
+            }
+
+            /// Returns whether or not this element is on the curve; should
+            /// always be true unless an "unchecked" API was used.
+            pub fn is_on_curve(&self) -> Choice {
+                // y^2 - x^3 - ax ?= b
+                (self.y.square() - (self.x.square() + &$name::curve_constant_a()) * self.x).ct_eq(&$name::curve_constant_b())
+                    | self.is_identity()
+            }
+
+            /// Adds two affine points, branching on the exceptional cases.
+            /// This is not constant time; use `+` for secret points.
+            pub fn add_vartime(&self, rhs: &$name_affine) -> $name {
+                if bool::from(self.is_identity()) {
+                    rhs.to_curve()
+                } else if bool::from(rhs.is_identity()) {
+                    self.to_curve()
+                } else {
+                    if self.x == rhs.x {
+                        if self.y == rhs.y {
+                            self.to_curve().double()
+                        } else {
+                            $name::identity()
+                        }
+                    } else {
+                        let h = rhs.x - self.x;
+                        let hh = h.square();
+                        let i = hh + hh;
+                        let i = i + i;
+                        let j = h * i;
+                        let r = rhs.y - self.y;
+                        let r = r + r;
+                        let v = self.x * i;
+                        let x3 = r.square() - j - v - v;
+                        let j = self.y * j;
+                        let j = j + j;
+                        let y3 = r * (v - x3) - j;
+                        let z3 = h + h;
+
+                        $name {
+                            x: x3, y: y3, z: z3
+                        }
+                    }
+                }
+            }
         }
 
         impl fmt::Debug for $name_affine {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-                if self.infinity.into() {
-                    write!(f, "Infinity")
+                if self.is_identity().into() {
+                    write!(f, "Identity")
                 } else {
                     write!(f, "({:?}, {:?})", self.x, self.y)
                 }
             }
         }
 
+        impl fmt::Debug for $name {
+            /// Prints the normalized affine coordinates of this point.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+                fmt::Debug::fmt(&self.to_affine(), f)
+            }
+        }
+
         impl group::Group for $name {
             type Scalar = $scalar;
 
-            fn random(mut rng: impl RngCore) -> Self {
-                loop {
-                    let x = $base::random(&mut rng);
-                    let ysign = (rng.next_u32() % 2) as u8;
-
-                    let x3 = x.square() * x;
-                    let y = (x3 + $name::curve_constant_b()).sqrt();
-                    if let Some(y) = Option::<$base>::from(y) {
-                        let sign = y.is_odd().unwrap_u8();
-                        let y = if ysign ^ sign == 0 { y } else { -y };
-
-                        let p = $name_affine {
-                            x,
-                            y,
-                            infinity: Choice::from(0u8),
-                        };
-                        break p.to_curve();
-                    }
-                }
+            /// Samples a uniformly random point as `k * G` for a uniformly
+            /// random scalar `k` and the generator `G`.
+            fn random(rng: impl RngCore) -> Self {
+                $name::generator() * $scalar::random(rng)
+            }
+
+            fn generator() -> Self {
+                $name::generator()
             }
 
             impl_projective_curve_specific!($name, $base, $curve_type);
@@ -99,12 +178,20 @@ macro_rules! new_curve_impl {
             }
 
             fn is_identity(&self) -> Choice {
-                self.z.is_zero()
+                $name::is_identity(self)
             }
         }
 
         #[cfg(feature = "std")]
         impl group::WnafGroup for $name {
+            #[cfg(feature = "opt-size")]
+            fn recommended_wnaf_for_num_scalars(_num_scalars: usize) -> usize {
+                // The smallest window `group::Wnaf` supports, which keeps the
+                // precomputed table to a single point.
+                2
+            }
+
+            #[cfg(not(feature = "opt-size"))]
             fn recommended_wnaf_for_num_scalars(num_scalars: usize) -> usize {
                 // Copied from bls12_381::g1, should be updated.
                 const RECOMMENDATIONS: [usize; 12] =
@@ -123,7 +210,6 @@ macro_rules! new_curve_impl {
             }
         }
 
-        #[cfg(feature = "std")]
         impl CurveExt for $name {
             type ScalarExt = $scalar;
             type Base = $base;
@@ -198,7 +284,6 @@ macro_rules! new_curve_impl {
 
                     q.x = p.x * tmp2;
                     q.y = p.y * tmp3;
-                    q.infinity = Choice::from(0u8);
 
                     *q = $name_affine::conditional_select(&q, &$name_affine::identity(), skip);
                 }
@@ -211,11 +296,7 @@ macro_rules! new_curve_impl {
                 let zinv3 = zinv2 * zinv;
                 let y = self.y * zinv3;
 
-                let tmp = $name_affine {
-                    x,
-                    y,
-                    infinity: Choice::from(0u8),
-                };
+                let tmp = $name_affine { x, y };
 
                 $name_affine::conditional_select(&tmp, &$name_affine::identity(), zinv.is_zero())
             }
@@ -298,52 +379,26 @@ macro_rules! new_curve_impl {
 
         impl cmp::Eq for $name {}
 
-        impl ConditionallySelectable for $name {
-            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-                $name {
-                    x: $base::conditional_select(&a.x, &b.x, choice),
-                    y: $base::conditional_select(&a.y, &b.y, choice),
-                    z: $base::conditional_select(&a.z, &b.z, choice),
-                }
-            }
-        }
-
-        impl<'a> Neg for &'a $name {
-            type Output = $name;
-
-            fn neg(self) -> $name {
-                $name {
-                    x: self.x,
-                    y: -self.y,
-                    z: self.z,
-                }
-            }
-        }
+        impl $name {
+            /// Compares this point with an affine point in constant time,
+            /// without normalizing this point.
+            pub fn ct_eq_affine(&self, other: &$name_affine) -> Choice {
+                // Is (x, y) equal to (x'/z'^2, y'/z'^3)?
 
-        impl Neg for $name {
-            type Output = $name;
+                let z2 = self.z.square();
+                let x = other.x * z2;
+                let y = other.y * z2 * self.z;
 
-            fn neg(self) -> $name {
-                -&self
-            }
-        }
+                let self_is_zero = self.is_identity();
+                let other_is_zero = other.is_identity();
 
-        impl<T> Sum<T> for $name
-        where
-            T: core::borrow::Borrow<$name>,
-        {
-            fn sum<I>(iter: I) -> Self
-            where
-                I: Iterator<Item = T>,
-            {
-                iter.fold(Self::identity(), |acc, item| acc + item.borrow())
+                (self_is_zero & other_is_zero)
+                    | ((!self_is_zero) & (!other_is_zero) & self.x.ct_eq(&x) & self.y.ct_eq(&y))
             }
-        }
-
-        impl<'a, 'b> Add<&'a $name> for &'b $name {
-            type Output = $name;
 
-            fn add(self, rhs: &'a $name) -> $name {
+            /// Adds `rhs` to this point, branching on the exceptional cases.
+            /// This is not constant time; use `+` for secret points.
+            pub fn add_vartime(&self, rhs: &$name) -> $name {
                 if bool::from(self.is_identity()) {
                     *rhs
                 } else if bool::from(rhs.is_identity()) {
@@ -382,12 +437,11 @@ macro_rules! new_curve_impl {
                     }
                 }
             }
-        }
 
-        impl<'a, 'b> Add<&'a $name_affine> for &'b $name {
-            type Output = $name;
-
-            fn add(self, rhs: &'a $name_affine) -> $name {
+            /// Adds the affine point `rhs` to this point, branching on the
+            /// exceptional cases. This is not constant time; use `+` for
+            /// secret points.
+            pub fn add_mixed_vartime(&self, rhs: &$name_affine) -> $name {
                 if bool::from(self.is_identity()) {
                     rhs.to_curve()
                 } else if bool::from(rhs.is_identity()) {
@@ -424,6 +478,170 @@ macro_rules! new_curve_impl {
                     }
                 }
             }
+
+            /// Multiplies this point by the 128-bit scalar `k`, negating the
+            /// result if `negate` is set. This does half the doublings and
+            /// additions of a full scalar multiplication, and is constant time
+            /// in `k` and `negate`.
+            pub fn mul_short(&self, k: u128, negate: bool) -> $name {
+                // `k` may be secret, so it and the multiples of `self` it
+                // produced are wiped afterwards.
+                #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+                let mut k = k;
+                let mut acc = $name::identity();
+                for i in (0..128).rev() {
+                    acc = acc.double();
+                    acc = $name::conditional_select(&acc, &(acc + self), Choice::from(((k >> i) & 1) as u8));
+                }
+                #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+                let mut neg = -acc;
+                let res = $name::conditional_select(&acc, &neg, Choice::from(negate as u8));
+
+                #[cfg(feature = "zeroize")]
+                {
+                    zeroize::Zeroize::zeroize(&mut k);
+                    zeroize::Zeroize::zeroize(&mut acc);
+                    zeroize::Zeroize::zeroize(&mut neg);
+                }
+
+                res
+            }
+
+            /// Multiplies this point by `scalar` with double-and-add, skipping
+            /// the additions for zero bits. This is not constant time; use
+            /// `*` for secret scalars.
+            pub fn mul_vartime(&self, scalar: &$scalar) -> $name {
+                let mut acc = $name::identity();
+                for byte in scalar.to_repr().iter().rev() {
+                    for i in (0..8).rev() {
+                        acc = acc.double();
+                        if (byte >> i) & 1 == 1 {
+                            acc = acc.add_vartime(self);
+                        }
+                    }
+                }
+                acc
+            }
+        }
+
+        impl PartialEq<$name_affine> for $name {
+            fn eq(&self, other: &$name_affine) -> bool {
+                self.ct_eq_affine(other).into()
+            }
+        }
+
+        impl PartialEq<$name> for $name_affine {
+            fn eq(&self, other: &$name) -> bool {
+                other.ct_eq_affine(self).into()
+            }
+        }
+
+        impl ConditionallySelectable for $name {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $name {
+                    x: $base::conditional_select(&a.x, &b.x, choice),
+                    y: $base::conditional_select(&a.y, &b.y, choice),
+                    z: $base::conditional_select(&a.z, &b.z, choice),
+                }
+            }
+        }
+
+        impl<'a> Neg for &'a $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name {
+                    x: self.x,
+                    y: -self.y,
+                    z: self.z,
+                }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                -&self
+            }
+        }
+
+        impl<T> Sum<T> for $name
+        where
+            T: core::borrow::Borrow<$name>,
+        {
+            fn sum<I>(iter: I) -> Self
+            where
+                I: Iterator<Item = T>,
+            {
+                iter.fold(Self::identity(), |acc, item| acc + item.borrow())
+            }
+        }
+
+        impl<'a, 'b> Add<&'a $name> for &'b $name {
+            type Output = $name;
+
+            fn add(self, rhs: &'a $name) -> $name {
+                // The exceptional cases are handled by selecting between the
+                // generic sum, the doubling and the two inputs, so that the
+                // same operations run for every pair of points.
+                let z1z1 = self.z.square();
+                let z2z2 = rhs.z.square();
+                let u1 = self.x * z2z2;
+                let u2 = rhs.x * z1z1;
+                let s1 = self.y * z2z2 * rhs.z;
+                let s2 = rhs.y * z1z1 * self.z;
+                let is_double = u1.ct_eq(&u2) & s1.ct_eq(&s2);
+
+                let h = u2 - u1;
+                let i = (h + h).square();
+                let j = h * i;
+                let r = s2 - s1;
+                let r = r + r;
+                let v = u1 * i;
+                let x3 = r.square() - j - v - v;
+                let s1 = s1 * j;
+                let s1 = s1 + s1;
+                let y3 = r * (v - x3) - s1;
+                // This is zero when u1 = u2, giving the identity for P + (-P).
+                let z3 = ((self.z + rhs.z).square() - z1z1 - z2z2) * h;
+
+                let res = $name { x: x3, y: y3, z: z3 };
+                let res = $name::conditional_select(&res, &self.double(), is_double);
+                let res = $name::conditional_select(&res, rhs, self.is_identity());
+                $name::conditional_select(&res, self, rhs.is_identity())
+            }
+        }
+
+        impl<'a, 'b> Add<&'a $name_affine> for &'b $name {
+            type Output = $name;
+
+            fn add(self, rhs: &'a $name_affine) -> $name {
+                // See the projective addition for the handling of the
+                // exceptional cases.
+                let z1z1 = self.z.square();
+                let u2 = rhs.x * z1z1;
+                let s2 = rhs.y * z1z1 * self.z;
+
+                let h = u2 - self.x;
+                let hh = h.square();
+                let i = hh + hh;
+                let i = i + i;
+                let j = h * i;
+                let r = s2 - self.y;
+                let r = r + r;
+                let v = self.x * i;
+                let x3 = r.square() - j - v - v;
+                let j = self.y * j;
+                let j = j + j;
+                let y3 = r * (v - x3) - j;
+                let z3 = (self.z + h).square() - z1z1 - hh;
+
+                let res = $name { x: x3, y: y3, z: z3 };
+                let res = $name::conditional_select(&res, &self.double(), self.x.ct_eq(&u2) & self.y.ct_eq(&s2));
+                let res = $name::conditional_select(&res, &rhs.to_curve(), self.is_identity());
+                $name::conditional_select(&res, self, rhs.is_identity())
+            }
         }
 
         impl<'a, 'b> Sub<&'a $name> for &'b $name {
@@ -451,13 +669,16 @@ macro_rules! new_curve_impl {
 
                 let mut acc = $name::identity();
 
+                // The scalar may be secret, so its bytes are wiped afterwards.
+                #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+                let mut repr = other.to_repr();
+
                 // This is a simple double-and-add implementation of point
                 // multiplication, moving from most significant to least
                 // significant bit of the scalar.
                 //
                 // NOTE: We skip the leading bit because it's always unset.
-                for bit in other
-                    .to_repr()
+                for bit in repr
                     .iter()
                     .rev()
                     .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
@@ -467,6 +688,9 @@ macro_rules! new_curve_impl {
                     acc = $name::conditional_select(&acc, &(acc + self), bit);
                 }
 
+                #[cfg(feature = "zeroize")]
+                zeroize::Zeroize::zeroize(&mut repr);
+
                 acc
             }
         }
@@ -478,7 +702,6 @@ macro_rules! new_curve_impl {
                 $name_affine {
                     x: self.x,
                     y: -self.y,
-                    infinity: self.infinity,
                 }
             }
         }
@@ -503,37 +726,7 @@ macro_rules! new_curve_impl {
             type Output = $name;
 
             fn add(self, rhs: &'a $name_affine) -> $name {
-                if bool::from(self.is_identity()) {
-                    rhs.to_curve()
-                } else if bool::from(rhs.is_identity()) {
-                    self.to_curve()
-                } else {
-                    if self.x == rhs.x {
-                        if self.y == rhs.y {
-                            self.to_curve().double()
-                        } else {
-                            $name::identity()
-                        }
-                    } else {
-                        let h = rhs.x - self.x;
-                        let hh = h.square();
-                        let i = hh + hh;
-                        let i = i + i;
-                        let j = h * i;
-                        let r = rhs.y - self.y;
-                        let r = r + r;
-                        let v = self.x * i;
-                        let x3 = r.square() - j - v - v;
-                        let j = self.y * j;
-                        let j = j + j;
-                        let y3 = r * (v - x3) - j;
-                        let z3 = h + h;
-
-                        $name {
-                            x: x3, y: y3, z: z3
-                        }
-                    }
-                }
+                self.to_curve() + rhs
             }
         }
 
@@ -562,13 +755,16 @@ macro_rules! new_curve_impl {
 
                 let mut acc = $name::identity();
 
+                // The scalar may be secret, so its bytes are wiped afterwards.
+                #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+                let mut repr = other.to_repr();
+
                 // This is a simple double-and-add implementation of point
                 // multiplication, moving from most significant to least
                 // significant bit of the scalar.
                 //
                 // NOTE: We skip the leading bit because it's always unset.
-                for bit in other
-                    .to_repr()
+                for bit in repr
                     .iter()
                     .rev()
                     .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
@@ -577,6 +773,9 @@ macro_rules! new_curve_impl {
                     acc = $name::conditional_select(&acc, &(acc + self), bit);
                 }
 
+                #[cfg(feature = "zeroize")]
+                zeroize::Zeroize::zeroize(&mut repr);
+
                 acc
             }
         }
@@ -585,25 +784,26 @@ macro_rules! new_curve_impl {
             type Curve = $name;
             type Scalar = $scalar;
 
-            impl_affine_curve_specific!($name, $base, $curve_type);
+            fn generator() -> Self {
+                $name_affine::generator()
+            }
 
             fn identity() -> Self {
                 Self {
                     x: $base::zero(),
                     y: $base::zero(),
-                    infinity: Choice::from(1u8),
                 }
             }
 
             fn is_identity(&self) -> Choice {
-                self.infinity
+                $name_affine::is_identity(self)
             }
 
             fn to_curve(&self) -> Self::Curve {
                 $name {
                     x: self.x,
                     y: self.y,
-                    z: $base::conditional_select(&$base::one(), &$base::zero(), self.infinity),
+                    z: $base::conditional_select(&$base::one(), &$base::zero(), self.is_identity()),
                 }
             }
         }
@@ -631,27 +831,29 @@ macro_rules! new_curve_impl {
 
 
 
-        #[cfg(feature = "std")]
         impl CurveAffine for $name_affine {
             type ScalarExt = $scalar;
             type Base = $base;
             type CurveExt = $name;
 
             fn is_on_curve(&self) -> Choice {
-                // y^2 - x^3 - ax ?= b
-                (self.y.square() - (self.x.square() + &$name::curve_constant_a()) * self.x).ct_eq(&$name::curve_constant_b())
-                    | self.infinity
+                $name_affine::is_on_curve(self)
+            }
+
+            #[cfg(not(feature = "pasta"))]
+            fn coordinates(&self) -> CtOption<Coordinates<Self>> {
+                CtOption::new(Coordinates { x: self.x, y: self.y }, !self.is_identity())
             }
 
+            /// Unimplemented: `pasta_curves` does not let other crates
+            /// construct its `Coordinates`.
+            #[cfg(feature = "pasta")]
             fn coordinates(&self) -> CtOption<Coordinates<Self>> {
-                // CtOption::new(Coordinates { x: self.x, y: self.y }, !self.is_identity())
                 unimplemented!()
             }
 
             fn from_xy(x: Self::Base, y: Self::Base) -> CtOption<Self> {
-                let p = $name_affine {
-                    x, y, infinity: 0u8.into()
-                };
+                let p = $name_affine { x, y };
                 CtOption::new(p, p.is_on_curve())
             }
 
@@ -670,6 +872,12 @@ macro_rules! new_curve_impl {
             }
         }
 
+        #[cfg(feature = "zeroize")]
+        impl zeroize::DefaultIsZeroes for $name {}
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::DefaultIsZeroes for $name_affine {}
+
         impl<'a> From<&'a $name> for $name_affine {
             fn from(p: &'a $name) -> $name_affine {
                 p.to_affine()
@@ -684,10 +892,7 @@ macro_rules! new_curve_impl {
 
         impl ConstantTimeEq for $name_affine {
             fn ct_eq(&self, other: &Self) -> Choice {
-                let z1 = self.infinity;
-                let z2 = other.infinity;
-
-                (z1 & z2) | ((!z1) & (!z2) & (self.x.ct_eq(&other.x)) & (self.y.ct_eq(&other.y)))
+                self.x.ct_eq(&other.x) & self.y.ct_eq(&other.y)
             }
         }
 
@@ -704,19 +909,154 @@ macro_rules! new_curve_impl {
                 $name_affine {
                     x: $base::conditional_select(&a.x, &b.x, choice),
                     y: $base::conditional_select(&a.y, &b.y, choice),
-                    infinity: Choice::conditional_select(&a.infinity, &b.infinity, choice),
                 }
             }
         }
 
+        #[cfg(feature = "std")]
+        impl SerdeObject for $name_affine {
+            fn from_raw_bytes_unchecked(bytes: &[u8]) -> Self {
+                assert_eq!(bytes.len(), 64);
+                $name_affine {
+                    x: $base::from_raw_bytes_unchecked(&bytes[..32]),
+                    y: $base::from_raw_bytes_unchecked(&bytes[32..]),
+                }
+            }
+
+            fn from_raw_bytes(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != 64 {
+                    return None;
+                }
+                let p = $name_affine {
+                    x: $base::from_raw_bytes(&bytes[..32])?,
+                    y: $base::from_raw_bytes(&bytes[32..])?,
+                };
+                Option::from(CtOption::new(p, p.is_on_curve()))
+            }
+
+            fn to_raw_bytes(&self) -> std::vec::Vec<u8> {
+                let mut res = self.x.to_raw_bytes();
+                res.extend(self.y.to_raw_bytes());
+                res
+            }
+
+            fn read_raw_unchecked<R: std::io::Read>(reader: &mut R) -> Self {
+                $name_affine {
+                    x: $base::read_raw_unchecked(reader),
+                    y: $base::read_raw_unchecked(reader),
+                }
+            }
+
+            fn read_raw<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+                let p = $name_affine {
+                    x: $base::read_raw(reader)?,
+                    y: $base::read_raw(reader)?,
+                };
+                Option::from(CtOption::new(p, p.is_on_curve())).ok_or_else(crate::serde::invalid_data)
+            }
+
+            fn write_raw<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                self.x.write_raw(writer)?;
+                self.y.write_raw(writer)
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl SerdeObject for $name {
+            fn from_raw_bytes_unchecked(bytes: &[u8]) -> Self {
+                assert_eq!(bytes.len(), 96);
+                $name {
+                    x: $base::from_raw_bytes_unchecked(&bytes[..32]),
+                    y: $base::from_raw_bytes_unchecked(&bytes[32..64]),
+                    z: $base::from_raw_bytes_unchecked(&bytes[64..]),
+                }
+            }
+
+            fn from_raw_bytes(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != 96 {
+                    return None;
+                }
+                let p = $name {
+                    x: $base::from_raw_bytes(&bytes[..32])?,
+                    y: $base::from_raw_bytes(&bytes[32..64])?,
+                    z: $base::from_raw_bytes(&bytes[64..])?,
+                };
+                Option::from(CtOption::new(p, p.is_on_curve()))
+            }
+
+            fn to_raw_bytes(&self) -> std::vec::Vec<u8> {
+                let mut res = self.x.to_raw_bytes();
+                res.extend(self.y.to_raw_bytes());
+                res.extend(self.z.to_raw_bytes());
+                res
+            }
+
+            fn read_raw_unchecked<R: std::io::Read>(reader: &mut R) -> Self {
+                $name {
+                    x: $base::read_raw_unchecked(reader),
+                    y: $base::read_raw_unchecked(reader),
+                    z: $base::read_raw_unchecked(reader),
+                }
+            }
+
+            fn read_raw<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+                let p = $name {
+                    x: $base::read_raw(reader)?,
+                    y: $base::read_raw(reader)?,
+                    z: $base::read_raw(reader)?,
+                };
+                Option::from(CtOption::new(p, p.is_on_curve())).ok_or_else(crate::serde::invalid_data)
+            }
+
+            fn write_raw<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                self.x.write_raw(writer)?;
+                self.y.write_raw(writer)?;
+                self.z.write_raw(writer)
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $name> for &'a $scalar {
+            type Output = $name;
+
+            fn mul(self, other: &'b $name) -> Self::Output {
+                other * self
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $name_affine> for &'a $scalar {
+            type Output = $name;
+
+            fn mul(self, other: &'b $name_affine) -> Self::Output {
+                other * self
+            }
+        }
+
         impl_binops_additive!($name, $name);
         impl_binops_additive!($name, $name_affine);
         impl_binops_additive_specify_output!($name_affine, $name_affine, $name);
         impl_binops_additive_specify_output!($name_affine, $name, $name);
         impl_binops_multiplicative!($name, $scalar);
         impl_binops_multiplicative_mixed!($name_affine, $scalar, $name);
+        impl_binops_multiplicative_mixed!($scalar, $name, $name);
+        impl_binops_multiplicative_mixed!($scalar, $name_affine, $name);
+
+        #[cfg(feature = "quickcheck")]
+        impl quickcheck::Arbitrary for $name {
+            /// Returns the generator times an arbitrary scalar, which is the
+            /// identity or the generator itself when the scalar is zero or
+            /// one.
+            fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+                $name::generator() * $scalar::arbitrary(g)
+            }
+        }
+
+        #[cfg(feature = "quickcheck")]
+        impl quickcheck::Arbitrary for $name_affine {
+            fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+                $name::arbitrary(g).to_affine()
+            }
+        }
 
-        #[cfg(feature = "std")]
         impl Group for $name {
             type Scalar = $scalar;
 
@@ -738,19 +1078,6 @@ macro_rules! new_curve_impl {
 
 macro_rules! impl_projective_curve_specific {
     ($name:ident, $base:ident, special_a0_b5) => {
-        fn generator() -> Self {
-            // NOTE: This is specific to b = 5
-
-            const NEGATIVE_ONE: $base = $base::neg(&$base::one());
-            const TWO: $base = $base::from_raw([2, 0, 0, 0]);
-
-            Self {
-                x: NEGATIVE_ONE,
-                y: TWO,
-                z: $base::one(),
-            }
-        }
-
         fn double(&self) -> Self {
             // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#doubling-dbl-2009-l
             //
@@ -762,16 +1089,13 @@ macro_rules! impl_projective_curve_specific {
             let d = self.x + b;
             let d = d.square();
             let d = d - a - c;
-            let d = d + d;
-            let e = a + a + a;
+            let d = d.double();
+            let e = a.mul3();
             let f = e.square();
             let z3 = self.z * self.y;
-            let z3 = z3 + z3;
-            let x3 = f - (d + d);
-            let c = c + c;
-            let c = c + c;
-            let c = c + c;
-            let y3 = e * (d - x3) - c;
+            let z3 = z3.double();
+            let x3 = f - d.double();
+            let y3 = e * (d - x3) - c.mul8();
 
             let tmp = $name {
                 x: x3,
@@ -783,11 +1107,6 @@ macro_rules! impl_projective_curve_specific {
         }
     };
     ($name:ident, $base:ident, general) => {
-        /// Unimplemented: there is no standard generator for this curve.
-        fn generator() -> Self {
-            unimplemented!()
-        }
-
         fn double(&self) -> Self {
             // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian.html#doubling-dbl-2007-bl
             //
@@ -798,12 +1117,9 @@ macro_rules! impl_projective_curve_specific {
             let a = yy.square();
             let zz = self.z.square();
             let s = ((self.x + yy).square() - xx - a).double();
-            let m = xx.double() + xx + $name::curve_constant_a() * zz.square();
+            let m = xx.mul3() + $name::curve_constant_a() * zz.square();
             let x3 = m.square() - s.double();
-            let a = a.double();
-            let a = a.double();
-            let a = a.double();
-            let y3 = m * (s - x3) - a;
+            let y3 = m * (s - x3) - a.mul8();
             let z3 = (self.y + self.z).square() - yy - zz;
 
             let tmp = $name {
@@ -817,9 +1133,9 @@ macro_rules! impl_projective_curve_specific {
     };
 }
 
-#[cfg(feature = "std")]
 macro_rules! impl_projective_curve_ext {
     ($name:ident, $base:ident, special_a0_b5) => {
+        #[cfg(feature = "std")]
         fn hash_to_curve<'a>(domain_prefix: &'a str) -> Box<dyn Fn(&[u8]) -> Self + 'a> {
             unimplemented!();
         }
@@ -832,41 +1148,24 @@ macro_rules! impl_projective_curve_ext {
     };
     ($name:ident, $base:ident, general) => {
         /// Unimplemented: hashing to this curve is not supported
+        #[cfg(feature = "std")]
         fn hash_to_curve<'a>(_domain_prefix: &'a str) -> Box<dyn Fn(&[u8]) -> Self + 'a> {
             unimplemented!()
         }
 
-        /// Unimplemented: no endomorphism is supported for this curve.
+        /// Applies the GLV endomorphism, multiplying the point by `ZETA`.
         fn endo(&self) -> Self {
-            unimplemented!()
-        }
-    };
-}
-
-macro_rules! impl_affine_curve_specific {
-    ($name:ident, $base:ident, special_a0_b5) => {
-        fn generator() -> Self {
-            // NOTE: This is specific to b = 5
-
-            const NEGATIVE_ONE: $base = $base::neg(&$base::from_raw([1, 0, 0, 0]));
-            const TWO: $base = $base::from_raw([2, 0, 0, 0]);
-
-            Self {
-                x: NEGATIVE_ONE,
-                y: TWO,
-                infinity: Choice::from(0u8),
-            }
-        }
-    };
-    ($name:ident, $base:ident, general) => {
-        /// Unimplemented: there is no standard generator for this curve.
-        fn generator() -> Self {
-            unimplemented!()
+            $name::endo(self)
         }
     };
 }
 
-/// Represents a point in bytes.
+/// Represents a point in bytes: the canonical little-endian encodings of the
+/// affine `x` and `y` coordinates, concatenated.
+///
+/// The identity is encoded as 64 zero bytes. Decoding rejects coordinates that
+/// are not fully reduced modulo `p` and any pair other than all zeros that is
+/// not on the curve, so every point has exactly one valid encoding.
 #[derive(Copy, Clone)]
 pub struct Serialized([u8; 64]);
 
@@ -876,8 +1175,8 @@ impl Default for Serialized {
     }
 }
 
-impl std::fmt::Debug for Serialized {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl fmt::Debug for Serialized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0[..].fmt(f)
     }
 }
@@ -898,66 +1197,153 @@ impl GroupEncoding for Secp256k1Affine {
     type Repr = Serialized;
 
     fn from_bytes(bytes: &Self::Repr) -> CtOption<Self> {
-        let bytes = bytes.as_ref();
-
-        if bytes == Serialized::default().as_ref() {
-            return CtOption::new(
-                Secp256k1Affine {
-                    x: Fp::zero(),
-                    y: Fp::zero(),
-                    infinity: Choice::from(1u8),
-                },
-                Choice::from(1u8),
-            );
-        }
-
-        let x_bytes: [u8; 32] = bytes[0..32].try_into().unwrap();
-        let y_bytes: [u8; 32] = bytes[32..64].try_into().unwrap();
-
-        let invalid = CtOption::new(
-            Secp256k1Affine {
-                x: Fp::zero(),
-                y: Fp::zero(),
-                infinity: Choice::from(0u8),
-            },
-            Choice::from(0u8),
-        );
-
-        let x = Fp::from_repr(x_bytes);
-        let y = Fp::from_repr(y_bytes);
+        let x_bytes: [u8; 32] = bytes.0[0..32].try_into().unwrap();
+        let y_bytes: [u8; 32] = bytes.0[32..64].try_into().unwrap();
 
-        if (x.is_none() | y.is_none()).into() {
-            return invalid;
-        } else {
-            let res = Secp256k1Affine {
-                x: x.unwrap(),
-                y: y.unwrap(),
-                infinity: Choice::from(0u8),
-            };
-            CtOption::new(res, res.is_on_curve())
-        }
+        Fp::from_repr(x_bytes).and_then(|x| {
+            Fp::from_repr(y_bytes).and_then(|y| {
+                // The identity is (0, 0), which is accepted by `is_on_curve`.
+                let p = Secp256k1Affine { x, y };
+                CtOption::new(p, p.is_on_curve())
+            })
+        })
     }
 
     fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
-        // We can't avoid curve checks when parsing a compressed encoding.
+        // The encoding is uncompressed `x || y`, so no square root is needed
+        // and the curve equation is the only check left. It is kept, since
+        // an off-curve point would silently break the group law.
         Self::from_bytes(bytes)
     }
 
     fn to_bytes(&self) -> Serialized {
-        // TODO: not constant time
-        if bool::from(self.is_identity()) {
-            Serialized::default()
+        // The identity is (0, 0), so it encodes to all zeros.
+        let mut res = Serialized::default();
+        res.0[0..32].copy_from_slice(&self.x.to_repr()[..]);
+        res.0[32..64].copy_from_slice(&self.y.to_repr()[..]);
+        res
+    }
+}
+
+/// A report on an encoded point, produced by [`Secp256k1Affine::validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointValidation {
+    /// Whether the `x` coordinate is fully reduced modulo `p`.
+    pub x_canonical: bool,
+    /// Whether the `y` coordinate is fully reduced modulo `p`.
+    pub y_canonical: bool,
+    /// Whether the coordinates satisfy the curve equation. This is `false`
+    /// whenever either coordinate is not canonical.
+    pub on_curve: bool,
+    /// Whether the encoding is that of the identity.
+    pub identity: bool,
+}
+
+impl PointValidation {
+    /// Returns whether `from_bytes` accepts the encoding.
+    pub fn is_valid(&self) -> bool {
+        self.x_canonical && self.y_canonical && (self.on_curve || self.identity)
+    }
+}
+
+impl Secp256k1Affine {
+    /// Decodes a point like `from_bytes`, returning early on the first check
+    /// that fails. This is not constant time; use `from_bytes` for secret
+    /// points.
+    pub fn from_bytes_vartime(bytes: &Serialized) -> Option<Self> {
+        let x_bytes: [u8; 32] = bytes.0[0..32].try_into().unwrap();
+        let y_bytes: [u8; 32] = bytes.0[32..64].try_into().unwrap();
+
+        let x = Option::<Fp>::from(Fp::from_repr(x_bytes))?;
+        let y = Option::<Fp>::from(Fp::from_repr(y_bytes))?;
+        let p = Secp256k1Affine { x, y };
+        if bool::from(p.is_on_curve()) {
+            Some(p)
         } else {
-            let x_bytes = self.x.to_repr();
-            let y_bytes = self.y.to_repr();
-            let mut ser: [u8; 64] = [0; 64];
-            ser[0..32].copy_from_slice(&x_bytes[..]);
-            ser[32..64].copy_from_slice(&y_bytes[..]);
-            Serialized(ser)
+            None
+        }
+    }
+
+    /// Checks an encoded point and reports each reason it may be rejected by
+    /// `from_bytes`, for use on untrusted inputs where the caller needs to
+    /// explain a rejection.
+    ///
+    /// This is not constant time; use `from_bytes` to decode secret points.
+    pub fn validate(bytes: &Serialized) -> PointValidation {
+        let x_bytes: [u8; 32] = bytes.0[0..32].try_into().unwrap();
+        let y_bytes: [u8; 32] = bytes.0[32..64].try_into().unwrap();
+
+        let x = Option::<Fp>::from(Fp::from_repr(x_bytes));
+        let y = Option::<Fp>::from(Fp::from_repr(y_bytes));
+        let identity = bytes.0.iter().all(|&b| b == 0);
+        let on_curve = match (x, y) {
+            (Some(x), Some(y)) if !identity => bool::from(Secp256k1Affine { x, y }.is_on_curve()),
+            _ => false,
+        };
+
+        PointValidation {
+            x_canonical: x.is_some(),
+            y_canonical: y.is_some(),
+            on_curve,
+            identity,
+        }
+    }
+}
+
+impl Secp256k1 {
+    /// Returns $\lambda \cdot P$ for $\lambda$ = `Fq::ZETA`, computed with
+    /// the endomorphism $(x, y) \mapsto (\beta x, y)$ for $\beta$ =
+    /// [`Fp::BETA`] at the cost of one field multiplication.
+    pub fn endo(&self) -> Self {
+        Secp256k1 {
+            x: self.x * Fp::BETA,
+            y: self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl Secp256k1Affine {
+    /// Returns $\lambda \cdot P$ for $\lambda$ = `Fq::ZETA`, as
+    /// [`Secp256k1::endo`] does. The identity is mapped to itself.
+    pub fn endo(&self) -> Self {
+        Secp256k1Affine {
+            x: self.x * Fp::BETA,
+            y: self.y,
+        }
+    }
+
+    /// Returns the point with the canonical coordinates `x` and `y`, without
+    /// checking that it is on the curve, for the tables generated by the
+    /// build script.
+    #[cfg(feature = "precomputed-tables")]
+    pub(crate) const fn from_raw_unchecked(x: [u64; 4], y: [u64; 4]) -> Self {
+        Secp256k1Affine {
+            x: Fp::from_raw(x),
+            y: Fp::from_raw(y),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl Secp256k1Affine {
+    /// Reads a point in the format of halo2's `CurveAffine::read`, which is
+    /// its `GroupEncoding`: 64 bytes holding `x || y`, with the identity as
+    /// all zeros.
+    pub fn read<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut repr = Serialized::default();
+        reader.read_exact(repr.as_mut())?;
+        Option::from(Self::from_bytes(&repr)).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid point encoding")
+        })
+    }
+
+    /// Writes this point in the format read by [`Secp256k1Affine::read`].
+    pub fn write<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(self.to_bytes().as_ref())
+    }
+}
+
 impl GroupEncoding for Secp256k1 {
     type Repr = Serialized;
 
@@ -966,8 +1352,9 @@ impl GroupEncoding for Secp256k1 {
     }
 
     fn from_bytes_unchecked(bytes: &Self::Repr) -> CtOption<Self> {
-        // We can't avoid curve checks when parsing a compressed encoding.
-        Secp256k1Affine::from_bytes(bytes).map(Self::from)
+        // See `Secp256k1Affine::from_bytes_unchecked`: the uncompressed
+        // encoding is still checked against the curve equation.
+        Secp256k1Affine::from_bytes_unchecked(bytes).map(Self::from)
     }
 
     fn to_bytes(&self) -> Self::Repr {
@@ -984,6 +1371,18 @@ new_curve_impl!(
     "secp256k1",
     [0, 0, 0, 0],
     [7, 0, 0, 0],
+    [
+        0x59f2_815b_16f8_1798,
+        0x029b_fcdb_2dce_28d9,
+        0x55a0_6295_ce87_0b07,
+        0x79be_667e_f9dc_bbac,
+    ],
+    [
+        0x9c47_d08f_fb10_d4b8,
+        0xfd17_b448_a685_5419,
+        0x5da4_fbfc_0e11_08a8,
+        0x483a_da77_26a3_c465,
+    ],
     general
 );
 
@@ -993,3 +1392,437 @@ fn test_curve() {
     use group::tests::curve_tests;
     curve_tests::<Secp256k1>();
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_ct_eq_affine() {
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    let q = Secp256k1::random(OsRng);
+    // Give `p` a non-trivial z coordinate.
+    let p = p.double() - p;
+    let identity = Secp256k1::identity();
+
+    assert_eq!(p, p.to_affine());
+    assert_eq!(p.to_affine(), p);
+    assert_ne!(p, q.to_affine());
+    assert_ne!(p, -p.to_affine());
+    assert_ne!(p, identity.to_affine());
+    assert_ne!(identity, p.to_affine());
+    assert_eq!(identity, identity.to_affine());
+    assert_eq!(p + (-p), identity.to_affine());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_conditional_negate() {
+    use rand::rngs::OsRng;
+    use subtle::ConditionallyNegatable;
+
+    let p = Secp256k1::random(OsRng);
+    let a = p.to_affine();
+
+    for &flag in [0u8, 1].iter() {
+        let choice = Choice::from(flag);
+        let expected = if flag == 1 { -p } else { p };
+
+        let mut q = p;
+        q.conditional_negate(choice);
+        assert_eq!(q, expected);
+
+        let mut b = a;
+        b.conditional_negate(choice);
+        assert_eq!(b, expected.to_affine());
+    }
+
+    assert_eq!(-a + a, Secp256k1::identity());
+    assert_eq!(-Secp256k1Affine::identity(), Secp256k1Affine::identity());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_is_identity() {
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    let a = p.to_affine();
+
+    assert!(bool::from(Secp256k1::identity().is_identity()));
+    assert!(bool::from(Secp256k1Affine::identity().is_identity()));
+    assert!(bool::from((p + (-p)).is_identity()));
+    assert!(bool::from((p + (-p)).to_affine().is_identity()));
+    assert!(!bool::from(p.is_identity()));
+    assert!(!bool::from(a.is_identity()));
+}
+
+#[test]
+fn test_generator() {
+    const G: Secp256k1Affine = Secp256k1Affine::generator();
+    const G_PROJ: Secp256k1 = Secp256k1::GENERATOR;
+
+    assert!(bool::from(G.is_on_curve()));
+    assert!(!bool::from(G.is_identity()));
+    assert_eq!(G, Secp256k1Affine::GENERATOR);
+    assert_eq!(G_PROJ, G);
+    assert_eq!(<Secp256k1Affine as PrimeCurveAffine>::generator(), G);
+    assert_eq!(<Secp256k1 as group::Group>::generator(), G_PROJ);
+}
+
+#[cfg(not(feature = "pasta"))]
+#[test]
+fn test_coordinates() {
+    let g = Secp256k1Affine::generator();
+    let coordinates = g.coordinates().unwrap();
+    assert_eq!(*coordinates.x(), g.x);
+    assert_eq!(*coordinates.y(), g.y);
+    assert_eq!(
+        Secp256k1Affine::from_xy(*coordinates.x(), *coordinates.y()).unwrap(),
+        g
+    );
+    assert!(bool::from(
+        Secp256k1Affine::identity().coordinates().is_none()
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_debug() {
+    use std::format;
+
+    let g = Secp256k1::generator();
+    let expected = "(0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798, \
+                    0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8)";
+
+    assert_eq!(format!("{:?}", g), expected);
+    assert_eq!(format!("{:?}", g.double() - g), expected);
+    assert_eq!(format!("{:?}", g.to_affine()), expected);
+    assert_eq!(format!("{:?}", Secp256k1::identity()), "Identity");
+    assert_eq!(format!("{:?}", Secp256k1Affine::identity()), "Identity");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_encoding() {
+    use rand::rngs::OsRng;
+
+    let identity = Secp256k1Affine::identity();
+    assert_eq!(identity.to_bytes().as_ref(), &[0u8; 64][..]);
+    assert_eq!(
+        Secp256k1Affine::from_bytes(&Serialized::default()).unwrap(),
+        identity
+    );
+
+    let p = Secp256k1::random(OsRng).to_affine();
+    let bytes = p.to_bytes();
+    assert_eq!(Secp256k1Affine::from_bytes(&bytes).unwrap(), p);
+
+    // (p, 0) would decode to the identity if non-canonical x were accepted.
+    let mut non_canonical = Serialized::default();
+    non_canonical.0[0..4].copy_from_slice(&[0x2f, 0xfc, 0xff, 0xff]);
+    non_canonical.0[4..8].copy_from_slice(&[0xfe, 0xff, 0xff, 0xff]);
+    for b in non_canonical.0[8..32].iter_mut() {
+        *b = 0xff;
+    }
+    assert!(bool::from(
+        Secp256k1Affine::from_bytes(&non_canonical).is_none()
+    ));
+
+    // Not on the curve.
+    let mut off_curve = bytes;
+    off_curve.0[32] ^= 1;
+    assert!(bool::from(
+        Secp256k1Affine::from_bytes(&off_curve).is_none()
+    ));
+
+    // All ones exceeds the modulus in both coordinates.
+    assert!(bool::from(
+        Secp256k1Affine::from_bytes(&Serialized([0xff; 64])).is_none()
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_cofactor() {
+    use group::cofactor::{CofactorCurve, CofactorCurveAffine, CofactorGroup};
+    use rand::rngs::OsRng;
+
+    fn check<C: CofactorCurve + CofactorGroup<Subgroup = C>>(p: C)
+    where
+        C::Affine: CofactorCurveAffine<Curve = C>,
+    {
+        assert_eq!(p.clear_cofactor(), p);
+        assert!(bool::from(p.is_torsion_free()));
+        assert_eq!(p.into_subgroup().unwrap(), p);
+        assert_eq!(p.to_affine().to_curve(), p);
+        assert!(bool::from(C::Affine::identity().is_identity()));
+        assert_eq!(C::Affine::generator().to_curve(), C::generator());
+    }
+
+    check(Secp256k1::random(OsRng));
+    check(Secp256k1::identity());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_validate() {
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng).to_affine();
+    let report = Secp256k1Affine::validate(&p.to_bytes());
+    assert!(report.is_valid());
+    assert!(report.on_curve && !report.identity);
+
+    let report = Secp256k1Affine::validate(&Serialized::default());
+    assert!(report.is_valid());
+    assert!(report.identity && !report.on_curve);
+
+    let mut off_curve = p.to_bytes();
+    off_curve.0[0] ^= 1;
+    let report = Secp256k1Affine::validate(&off_curve);
+    assert!(!report.is_valid());
+    assert!(report.x_canonical && report.y_canonical && !report.on_curve);
+
+    let mut non_canonical = p.to_bytes();
+    for b in non_canonical.0[32..64].iter_mut() {
+        *b = 0xff;
+    }
+    let report = Secp256k1Affine::validate(&non_canonical);
+    assert!(!report.is_valid());
+    assert!(report.x_canonical && !report.y_canonical && !report.on_curve);
+
+    for bytes in [
+        p.to_bytes(),
+        Serialized::default(),
+        off_curve,
+        non_canonical,
+    ]
+    .iter()
+    {
+        assert_eq!(
+            Secp256k1Affine::validate(bytes).is_valid(),
+            bool::from(Secp256k1Affine::from_bytes(bytes).is_some())
+        );
+    }
+}
+
+#[test]
+fn test_endo() {
+    use crate::arithmetic::FieldExt;
+
+    let g = Secp256k1::generator();
+    let p = g * Fq::from(0x1234_5678);
+    for point in [g, p, p.double() + g, Secp256k1::identity()].iter() {
+        let expected = point * Fq::ZETA;
+        assert_eq!(point.endo(), expected);
+        assert_eq!(point.to_affine().endo(), expected.to_affine());
+        assert_eq!(point.endo().endo().endo(), *point);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_vartime() {
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    let q = Secp256k1::random(OsRng);
+    let identity = Secp256k1::identity();
+    let points = [p, q, -p, p.double(), identity];
+
+    for a in points.iter() {
+        for b in points.iter() {
+            let expected = a.add_vartime(b);
+            assert_eq!(a + b, expected);
+            assert_eq!(a + b.to_affine(), expected);
+            assert_eq!(a.add_mixed_vartime(&b.to_affine()), expected);
+            assert_eq!(a.to_affine() + b.to_affine(), expected);
+            assert_eq!(a.to_affine().add_vartime(&b.to_affine()), expected);
+        }
+    }
+    assert!(bool::from((p + (-p)).is_identity()));
+    assert_eq!(p + p, p.double());
+    assert_eq!(p + p.to_affine(), p.double());
+
+    for k in [Fq::zero(), Fq::one(), -Fq::one(), Fq::random(OsRng)].iter() {
+        assert_eq!(p.mul_vartime(k), p * k);
+    }
+
+    let bytes = p.to_affine().to_bytes();
+    assert_eq!(
+        Secp256k1Affine::from_bytes_vartime(&bytes),
+        Some(p.to_affine())
+    );
+    assert_eq!(
+        Secp256k1Affine::from_bytes_vartime(&Serialized::default()),
+        Some(Secp256k1Affine::identity())
+    );
+    let mut off_curve = bytes;
+    off_curve.0[0] ^= 1;
+    assert_eq!(Secp256k1Affine::from_bytes_vartime(&off_curve), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_mul_short() {
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    for &k in [0u128, 1, 2, u64::MAX as u128, u128::MAX].iter() {
+        let scalar = Fq::from_u128(k);
+        assert_eq!(p.mul_short(k, false), p * scalar);
+        assert_eq!(p.mul_short(k, true), -(p * scalar));
+    }
+
+    let k = ((OsRng.next_u64() as u128) << 64) | OsRng.next_u64() as u128;
+    assert_eq!(p.mul_short(k, true), p * -Fq::from_u128(k));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_scalar_mul_left() {
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    let a = p.to_affine();
+    let k = Fq::random(OsRng);
+    assert_eq!(k * p, p * k);
+    assert_eq!(&k * &p, p * k);
+    assert_eq!(k * &p, p * k);
+    assert_eq!(&k * p, p * k);
+    assert_eq!(k * a, a * k);
+    assert_eq!(&k * &a, a * k);
+
+    let generator = Secp256k1::generator();
+    assert_eq!(k * (k * generator + k * a), (generator * k + a * k) * k);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_random() {
+    use rand::rngs::OsRng;
+
+    fn sample<G: group::Group>() -> G {
+        G::random(OsRng)
+    }
+
+    let p: Secp256k1 = sample();
+    let q: Secp256k1 = sample();
+    assert!(bool::from(p.to_affine().is_on_curve()));
+    assert!(!bool::from(p.is_identity()));
+    assert!(p != q);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_default() {
+    let points: std::vec::Vec<Secp256k1> = vec![Default::default(); 4];
+    assert!(points.iter().all(|p| bool::from(p.is_identity())));
+    assert!(bool::from(Secp256k1Affine::default().is_identity()));
+
+    let identity = Secp256k1::default();
+    let bytes = identity.to_bytes();
+    assert_eq!(bytes.as_ref(), Serialized::default().as_ref());
+    assert_eq!(Secp256k1::from_bytes(&bytes).unwrap(), identity);
+    assert_eq!(
+        Secp256k1Affine::from_bytes(&Secp256k1Affine::default().to_bytes()).unwrap(),
+        Secp256k1Affine::default()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_read_write() {
+    use rand::rngs::OsRng;
+    use std::vec::Vec;
+
+    let points = [
+        Secp256k1::random(OsRng).to_affine(),
+        Secp256k1Affine::identity(),
+        Secp256k1Affine::generator(),
+    ];
+
+    let mut buf = Vec::new();
+    for p in points.iter() {
+        p.write(&mut buf).unwrap();
+    }
+    assert_eq!(buf.len(), 64 * points.len());
+    assert_eq!(&buf[..64], points[0].to_bytes().as_ref());
+
+    let mut reader = &buf[..];
+    for p in points.iter() {
+        assert_eq!(Secp256k1Affine::read(&mut reader).unwrap(), *p);
+    }
+    assert!(Secp256k1Affine::read(&mut reader).is_err());
+
+    let mut invalid = buf[..64].to_vec();
+    invalid[0] ^= 1;
+    let err = Secp256k1Affine::read(&mut &invalid[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_serde_object() {
+    use rand::rngs::OsRng;
+    use std::vec::Vec;
+
+    let p = Secp256k1::random(OsRng);
+    for p in [p, p.double(), Secp256k1::identity()].iter() {
+        let bytes = p.to_raw_bytes();
+        assert_eq!(bytes.len(), 96);
+        assert_eq!(Secp256k1::from_raw_bytes(&bytes).unwrap(), *p);
+        assert_eq!(Secp256k1::from_raw_bytes_unchecked(&bytes), *p);
+
+        let a = p.to_affine();
+        let mut buf = Vec::new();
+        a.write_raw(&mut buf).unwrap();
+        assert_eq!(buf, a.to_raw_bytes());
+        assert_eq!(Secp256k1Affine::read_raw(&mut &buf[..]).unwrap(), a);
+        assert_eq!(Secp256k1Affine::read_raw_unchecked(&mut &buf[..]), a);
+    }
+
+    let mut off_curve = Secp256k1Affine::generator().to_raw_bytes();
+    off_curve[0] ^= 1;
+    assert!(Secp256k1Affine::from_raw_bytes(&off_curve).is_none());
+    assert!(Secp256k1Affine::read_raw(&mut &off_curve[..]).is_err());
+    assert!(Secp256k1::from_raw_bytes(&[0xff; 96]).is_none());
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn test_arbitrary() {
+    use quickcheck::{Arbitrary, Gen};
+
+    let mut g = Gen::new(100);
+    let mut edge_cases = 0;
+    for _ in 0..200 {
+        let scalar = Fq::arbitrary(&mut g);
+        let base = Fp::arbitrary(&mut g);
+        if bool::from(scalar.is_zero()) || scalar == Fq::one() || scalar == -Fq::one() {
+            edge_cases += 1;
+        }
+        assert!(bool::from(Fp::from_repr(base.to_repr()).is_some()));
+
+        let point = Secp256k1::arbitrary(&mut g);
+        let affine = Secp256k1Affine::arbitrary(&mut g);
+        assert!(bool::from(point.is_on_curve()));
+        assert!(bool::from(affine.is_on_curve()));
+    }
+    assert!(edge_cases > 0 && edge_cases < 100);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+    use rand::rngs::OsRng;
+    use zeroize::Zeroize;
+
+    let mut p = Secp256k1::random(OsRng);
+    p.zeroize();
+    assert!(bool::from(p.is_identity()));
+
+    let mut p = Secp256k1::random(OsRng).to_affine();
+    p.zeroize();
+    assert!(bool::from(p.is_identity()));
+}
@@ -0,0 +1,157 @@
+//! This module contains differential checks of this crate against the `k256`
+//! crate.
+//!
+//! Each check runs the same random operations through both implementations
+//! and panics on the first result that differs. Base field arithmetic is
+//! covered through the point operations, since `k256` does not expose its
+//! field element type by default. Signatures are compared through the
+//! `hazmat` signing primitive of the `ecdsa` crate, which takes the nonce
+//! as an argument.
+
+use ecdsa_core::hazmat::{SignPrimitive, VerifyPrimitive};
+use ff::{Field, PrimeField};
+use group::{Curve, Group, GroupEncoding};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use rand::RngCore;
+
+use crate::ecdsa::normalize_s;
+use crate::hazmat::{
+    challenge_from_digest, sign_prehashed_with_nonce, verify_prehashed_with_generator,
+};
+
+use super::{Fq, Secp256k1, Secp256k1Affine};
+
+/// Converts a `k256` scalar into an `Fq`. `k256` encodes scalars big-endian.
+fn scalar_from_k256(s: &k256::Scalar) -> Fq {
+    let mut repr = [0u8; 32];
+    repr.copy_from_slice(&s.to_repr());
+    repr.reverse();
+    Fq::from_repr(repr).unwrap()
+}
+
+/// Converts an `Fq` into a `k256` scalar.
+fn scalar_to_k256(s: &Fq) -> k256::Scalar {
+    let mut repr = s.to_repr();
+    repr.reverse();
+    k256::Scalar::from_repr(repr.into()).unwrap()
+}
+
+/// Converts a `k256` point into a `Secp256k1` point by way of the uncompressed
+/// SEC1 encoding.
+fn point_from_k256(p: &k256::ProjectivePoint) -> Secp256k1 {
+    let encoded = p.to_affine().to_encoded_point(false);
+    let bytes = encoded.as_bytes();
+    if bytes.len() == 1 {
+        return Secp256k1::identity();
+    }
+
+    let mut repr = <Secp256k1Affine as GroupEncoding>::Repr::default();
+    let (x, y) = repr.as_mut().split_at_mut(32);
+    x.copy_from_slice(&bytes[1..33]);
+    y.copy_from_slice(&bytes[33..65]);
+    x.reverse();
+    y.reverse();
+    Secp256k1Affine::from_bytes(&repr).unwrap().into()
+}
+
+/// Runs `iterations` rounds of scalar field operations on random inputs
+/// through both implementations and panics if any result differs.
+pub fn check_scalars(mut rng: impl RngCore, iterations: usize) {
+    for _ in 0..iterations {
+        let a = k256::Scalar::random(&mut rng);
+        let b = k256::Scalar::random(&mut rng);
+        let (x, y) = (scalar_from_k256(&a), scalar_from_k256(&b));
+
+        assert_eq!(x + y, scalar_from_k256(&(a + b)), "add");
+        assert_eq!(x - y, scalar_from_k256(&(a - b)), "sub");
+        assert_eq!(x * y, scalar_from_k256(&(a * b)), "mul");
+        assert_eq!(-x, scalar_from_k256(&(-a)), "neg");
+        assert_eq!(x.square(), scalar_from_k256(&a.square()), "square");
+        assert_eq!(x.double(), scalar_from_k256(&a.double()), "double");
+        assert_eq!(
+            x.invert().unwrap(),
+            scalar_from_k256(&a.invert().unwrap()),
+            "invert"
+        );
+    }
+}
+
+/// Runs `iterations` rounds of point operations on random inputs through
+/// both implementations and panics if any result differs.
+pub fn check_points(mut rng: impl RngCore, iterations: usize) {
+    for _ in 0..iterations {
+        let a = k256::ProjectivePoint::random(&mut rng);
+        let b = k256::ProjectivePoint::random(&mut rng);
+        let k = k256::Scalar::random(&mut rng);
+        let (p, q, s) = (
+            point_from_k256(&a),
+            point_from_k256(&b),
+            scalar_from_k256(&k),
+        );
+
+        assert_eq!(p + q, point_from_k256(&(a + b)), "add");
+        assert_eq!(p - q, point_from_k256(&(a - b)), "sub");
+        assert_eq!(p + p.to_affine(), point_from_k256(&(a + a)), "mixed add");
+        assert_eq!(p.double(), point_from_k256(&a.double()), "double");
+        assert_eq!(-p, point_from_k256(&(-a)), "neg");
+        assert_eq!(p - p, point_from_k256(&(a - a)), "identity");
+        assert_eq!(p * s, point_from_k256(&(a * k)), "mul");
+    }
+}
+
+/// Runs `iterations` rounds of ECDSA signing with random keys, nonces and
+/// digests through both implementations and panics if the low-S signatures
+/// differ or if either implementation rejects a signature made by the other.
+pub fn check_signatures(mut rng: impl RngCore, iterations: usize) {
+    for _ in 0..iterations {
+        let d = k256::NonZeroScalar::random(&mut rng);
+        let k = k256::NonZeroScalar::random(&mut rng);
+        let mut digest = [0u8; 32];
+        rng.fill_bytes(&mut digest);
+
+        // `k256` signs in low-S form.
+        let (signature, recovery_id) = d.try_sign_prehashed(k, digest.into()).unwrap();
+        let z = challenge_from_digest(&digest);
+        let secret_key = scalar_from_k256(&d);
+        let (r, s, id) = sign_prehashed_with_nonce(&secret_key, &scalar_from_k256(&k), &z).unwrap();
+        let (s, id) = normalize_s(&s, id);
+
+        assert_eq!(r, scalar_from_k256(&signature.r()), "r");
+        assert_eq!(s, scalar_from_k256(&signature.s()), "s");
+        assert_eq!(
+            id.is_y_odd(),
+            recovery_id.unwrap().is_y_odd(),
+            "recovery id"
+        );
+
+        let public_key = Secp256k1::generator() * secret_key;
+        assert!(
+            verify_prehashed_with_generator(
+                &Secp256k1::generator(),
+                &public_key,
+                &z,
+                &scalar_from_k256(&signature.r()),
+                &scalar_from_k256(&signature.s()),
+            ),
+            "verify k256 signature"
+        );
+        let ours =
+            k256::ecdsa::Signature::from_scalars(scalar_to_k256(&r), scalar_to_k256(&s)).unwrap();
+        let k256_public_key = (k256::ProjectivePoint::generator() * *d).to_affine();
+        assert!(
+            k256_public_key
+                .verify_prehashed(digest.into(), &ours)
+                .is_ok(),
+            "k256 verify signature"
+        );
+    }
+}
+
+#[test]
+fn test_differential() {
+    use rand::rngs::OsRng;
+
+    check_scalars(OsRng, 1000);
+    check_points(OsRng, 100);
+    check_signatures(OsRng, 100);
+}
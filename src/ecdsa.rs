@@ -0,0 +1,924 @@
+//! This module contains ECDSA utilities. Signatures are given as the scalars
+//! $(r, s)$ together with the message hash $z$, all reduced modulo $q$.
+//!
+//! It also contains the scalar and point arithmetic of two-party and
+//! threshold ECDSA, with the messages between the parties left to the
+//! caller.
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use core::fmt;
+
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::Curve;
+use rand::RngCore;
+
+use crate::glv::glv_double_mul_vartime;
+use crate::hazmat::{is_r_of_point, r_from_point, verify_prehashed_with_generator};
+use crate::wnaf::Wnaf;
+#[cfg(feature = "precomputed-tables")]
+use crate::wnaf::GENERATOR_WNAF_TABLE_SIZE;
+
+use super::{Fq, Secp256k1, Secp256k1Affine};
+
+/// Recovers the nonce and private key from two ECDSA signatures by the key
+/// `public_key` that share the same `r` over different message hashes, given
+/// as `(s, z)` pairs.
+///
+/// Since either signature may have been normalized to low-S, both signs of
+/// `s` are tried and each candidate key is checked against `public_key`.
+/// Returns `(k, d)` for the first signature's nonce `k` and the private key
+/// `d`, or `None` if the signatures do not reuse a nonce of this key.
+///
+/// This is not constant time; it is meant for auditing published signatures.
+pub fn recover_reused_nonce(
+    public_key: &Secp256k1,
+    r: &Fq,
+    (s1, z1): (Fq, Fq),
+    (s2, z2): (Fq, Fq),
+) -> Option<(Fq, Fq)> {
+    let r_inv = Option::<Fq>::from(r.invert())?;
+
+    // s_i = k^-1 (z_i + r d) up to sign, so k = (z1 - z2) / (s1 -+ s2) and
+    // d = (s1 k - z1) / r.
+    for s2 in [s2, -s2].iter() {
+        let k = match Option::<Fq>::from((s1 - s2).invert()) {
+            Some(inv) => (z1 - z2) * inv,
+            None => continue,
+        };
+        let d = (s1 * k - z1) * r_inv;
+        if Secp256k1::generator() * d == *public_key {
+            return Some((k, d));
+        }
+    }
+    None
+}
+
+/// The recovery id of an ECDSA signature, identifying the point $R$ whose
+/// x-coordinate gave `r`.
+///
+/// Bit 0 is set if $R$ has odd y, and bit 1 is set if the x-coordinate of
+/// $R$ was at least $q$ and was reduced to get `r`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecoveryId(u8);
+
+impl RecoveryId {
+    /// Returns the recovery id for the given parity of $R_y$ and whether
+    /// $R_x$ was reduced modulo $q$.
+    pub fn new(is_y_odd: bool, is_x_reduced: bool) -> Self {
+        RecoveryId(is_y_odd as u8 | (is_x_reduced as u8) << 1)
+    }
+
+    /// Returns the recovery id encoded as `byte`, failing if it is not less
+    /// than 4.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        if byte < 4 {
+            Some(RecoveryId(byte))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the recovery id as a byte less than 4.
+    pub fn to_byte(self) -> u8 {
+        self.0
+    }
+
+    /// Returns whether $R$ has odd y.
+    pub fn is_y_odd(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// Returns whether the x-coordinate of $R$ was reduced modulo $q$.
+    pub fn is_x_reduced(self) -> bool {
+        self.0 & 2 == 2
+    }
+
+    /// Returns the Ethereum `v` value for this recovery id: `27 + id` for
+    /// legacy signatures when `chain_id` is `None`, and
+    /// `chain_id * 2 + 35 + id` as specified by EIP-155 otherwise.
+    ///
+    /// Fails if the x-coordinate of $R$ was reduced, which Ethereum cannot
+    /// express, or if `v` does not fit in a `u64`.
+    pub fn to_eth_v(self, chain_id: Option<u64>) -> Option<u64> {
+        if self.is_x_reduced() {
+            return None;
+        }
+        let id = u64::from(self.0);
+        match chain_id {
+            None => Some(27 + id),
+            Some(chain_id) => chain_id.checked_mul(2)?.checked_add(35 + id),
+        }
+    }
+
+    /// Parses an Ethereum `v` value into a recovery id and, for EIP-155
+    /// values, the chain id. This is the inverse of [`RecoveryId::to_eth_v`].
+    pub fn from_eth_v(v: u64) -> Option<(Self, Option<u64>)> {
+        match v {
+            27 | 28 => Some((RecoveryId((v - 27) as u8), None)),
+            35..=u64::MAX => {
+                let id = RecoveryId(((v - 35) % 2) as u8);
+                Some((id, Some((v - 35) / 2)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes the signature value `s` to low-S form, $s \le (q - 1) / 2$,
+/// returning the new `s` and the recovery id adjusted for it: negating `s`
+/// negates $R$ and so flips the parity of its y-coordinate.
+///
+/// This is not constant time in `s`.
+pub fn normalize_s(s: &Fq, recovery_id: RecoveryId) -> (Fq, RecoveryId) {
+    let neg = -s;
+    if neg < *s {
+        (neg, RecoveryId(recovery_id.0 ^ 1))
+    } else {
+        (*s, recovery_id)
+    }
+}
+
+/// An ECDSA public key with the wNAF tables used to verify its signatures,
+/// so that a stream of signatures by one signer does not rebuild them on
+/// every call.
+///
+/// The key holds [`Wnaf`] tables of `N` affine points each for the public key
+/// $Q$, the generator $G$ and their images $\lambda Q$ and $\lambda G$ under
+/// the GLV endomorphism, so it takes about $256 N$ bytes. `N` must be a power
+/// of two no larger than 64; 64 gives the fastest verification.
+#[derive(Clone, Debug)]
+pub struct PrecomputedVerifyingKey<const N: usize> {
+    public_key: Secp256k1Affine,
+    key: Wnaf<N>,
+    key_endo: Wnaf<N>,
+    generator: Wnaf<N>,
+    generator_endo: Wnaf<N>,
+}
+
+impl<const N: usize> PrecomputedVerifyingKey<N> {
+    /// Precomputes the tables for `public_key`.
+    ///
+    /// Panics if `N` is not a power of two or is larger than 64.
+    pub fn new(public_key: &Secp256k1) -> Self {
+        let key = Wnaf::new(public_key);
+        let generator = Wnaf::new(&Secp256k1::generator());
+        PrecomputedVerifyingKey {
+            public_key: public_key.to_affine(),
+            key_endo: key.endo(),
+            key,
+            generator_endo: generator.endo(),
+            generator,
+        }
+    }
+
+    /// Returns the public key.
+    pub fn public_key(&self) -> Secp256k1Affine {
+        self.public_key
+    }
+
+    /// Checks the signature $(r, s)$ of the message hash `z` by this key, as
+    /// [`crate::hazmat::verify_prehashed_with_generator`] does with the
+    /// standard generator but reusing the precomputed tables.
+    ///
+    /// Both low-S and high-S signatures are accepted. This is not constant
+    /// time: every input is public.
+    pub fn verify_prehashed(&self, z: &Fq, r: &Fq, s: &Fq) -> bool {
+        if bool::from(r.is_zero()) {
+            return false;
+        }
+        let s_inv = match Option::<Fq>::from(s.invert()) {
+            Some(s_inv) => s_inv,
+            None => return false,
+        };
+
+        let point = glv_double_mul_vartime(
+            &(z * s_inv),
+            (&self.generator, &self.generator_endo),
+            &(r * s_inv),
+            (&self.key, &self.key_endo),
+        );
+        is_r_of_point(&point.to_affine(), r)
+    }
+}
+
+/// ECDSA signatures queued for verification together, sharing the work that
+/// does not depend on the individual checks: one batch inversion of all the
+/// `s` values, one batch normalization of all the recomputed points $R_i$,
+/// and one pair of width-8 tables for the generator, of 64 points each, or
+/// the tables generated at build time with the `precomputed-tables` feature.
+///
+/// Each signature still costs one GLV double multiplication. ECDSA compares
+/// the x-coordinate of each $R_i$ separately, so the points cannot be folded
+/// into a single multi-scalar multiplication without recovering them from
+/// `r`, which is what probabilistic batch verification does instead.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct VerificationBatch {
+    signatures: Vec<(Fq, Fq, Fq, Secp256k1)>,
+}
+
+#[cfg(feature = "std")]
+impl VerificationBatch {
+    /// Returns an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the signature $(r, s)$ of the message hash `z` by
+    /// `public_key`.
+    pub fn push(&mut self, z: &Fq, r: &Fq, s: &Fq, public_key: &Secp256k1) {
+        self.signatures.push((*z, *r, *s, *public_key));
+    }
+
+    /// Returns the number of queued signatures.
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Returns whether no signature is queued.
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Returns whether every queued signature is valid, with the same result
+    /// as checking each one with
+    /// [`crate::hazmat::verify_prehashed_with_generator`] and the standard
+    /// generator. An empty batch is valid.
+    ///
+    /// This is not constant time: every input is public.
+    pub fn finalize(self) -> bool {
+        if self
+            .signatures
+            .iter()
+            .any(|(_, r, s, _)| bool::from(r.is_zero() | s.is_zero()))
+        {
+            return false;
+        }
+
+        let s_values: Vec<Fq> = self.signatures.iter().map(|(_, _, s, _)| *s).collect();
+        let s_inverses = Fq::batch_inverted(&s_values);
+
+        #[cfg(feature = "precomputed-tables")]
+        let (generator, generator_endo) = (
+            &Wnaf::<{ GENERATOR_WNAF_TABLE_SIZE }>::GENERATOR,
+            &Wnaf::<{ GENERATOR_WNAF_TABLE_SIZE }>::GENERATOR_ENDO,
+        );
+        #[cfg(not(feature = "precomputed-tables"))]
+        let generator = &Wnaf::<64>::new(&Secp256k1::generator());
+        #[cfg(not(feature = "precomputed-tables"))]
+        let generator_endo = &generator.endo();
+        let points: Vec<Secp256k1> = self
+            .signatures
+            .iter()
+            .zip(s_inverses.iter())
+            .map(|((z, r, _, public_key), s_inv)| {
+                let key = Wnaf::<8>::new(public_key);
+                glv_double_mul_vartime(
+                    &(z * s_inv),
+                    (generator, generator_endo),
+                    &(r * s_inv),
+                    (&key, &key.endo()),
+                )
+            })
+            .collect();
+
+        let mut affine = vec![Secp256k1Affine::default(); points.len()];
+        Secp256k1::batch_normalize(&points, &mut affine);
+        affine
+            .iter()
+            .zip(self.signatures.iter())
+            .all(|(point, (_, r, _, _))| is_r_of_point(point, r))
+    }
+}
+
+/// One party's multiplicative share $x_i$ of a two-party ECDSA key, following
+/// Lindell's 2-of-2 protocol: the private key is $x = x_1 x_2$ and the public
+/// key is $Q = x_1 x_2 G$.
+///
+/// The second party contributes to a signature with
+/// [`TwoPartyKeyShare::partial_sign`], evaluated under the first party's
+/// additively homomorphic encryption of $x_1$, and the first party completes
+/// it with [`TwoPartyNonce::complete_signature`]. The encryption scheme and
+/// the zero-knowledge proofs that each party knows its shares are left to
+/// the caller.
+#[derive(Clone)]
+pub struct TwoPartyKeyShare {
+    secret: Fq,
+    public_share: Secp256k1Affine,
+}
+
+impl TwoPartyKeyShare {
+    /// Returns the share with the secret `secret`, or `None` if it is zero.
+    pub fn new(secret: Fq) -> Option<Self> {
+        if bool::from(secret.is_zero()) {
+            return None;
+        }
+        Some(TwoPartyKeyShare {
+            secret,
+            public_share: (Secp256k1::generator() * secret).to_affine(),
+        })
+    }
+
+    /// Returns the secret share $x_i$, which the first party encrypts for
+    /// the second.
+    pub fn secret(&self) -> Fq {
+        self.secret
+    }
+
+    /// Returns the public share $Q_i = x_i G$, sent to the other party.
+    pub fn public_share(&self) -> Secp256k1Affine {
+        self.public_share
+    }
+
+    /// Returns the joint public key $Q = x_i Q_j$ given the public share
+    /// $Q_j$ of the other party. This is constant time in the secret share.
+    pub fn joint_public_key(&self, other_public_share: &Secp256k1Affine) -> Secp256k1Affine {
+        (other_public_share * self.secret).to_affine()
+    }
+
+    /// Computes the second party's contribution to the signature of the
+    /// challenge `z`, given its nonce share and the joint `r` from
+    /// [`TwoPartyNonce::joint_r`]. This is constant time in the shares.
+    pub fn partial_sign(&self, nonce: &TwoPartyNonce, r: &Fq, z: &Fq) -> PartialSignature {
+        // The nonce share is nonzero, so it has an inverse.
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut nonce_inv = nonce.secret.invert().unwrap();
+        let offset = nonce_inv * z;
+        let coefficient = nonce_inv * r * self.secret;
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut nonce_inv);
+
+        PartialSignature {
+            offset,
+            coefficient,
+        }
+    }
+}
+
+impl fmt::Debug for TwoPartyKeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TwoPartyKeyShare")
+            .field("public_share", &self.public_share)
+            .finish()
+    }
+}
+
+/// One party's multiplicative share $k_i$ of the nonce of a two-party
+/// signature, with the commitment $R_i = k_i G$ it sends to the other party.
+/// The joint nonce is $k = k_1 k_2$, so $R = k_1 k_2 G$.
+///
+/// A nonce share must never sign twice. In the protocol the first party
+/// commits to $R_1$ before it sees $R_2$, and both parties prove that they
+/// know the discrete logarithms of their commitments; those steps are left
+/// to the caller.
+#[derive(Clone)]
+pub struct TwoPartyNonce {
+    secret: Fq,
+    commitment: Secp256k1Affine,
+}
+
+impl TwoPartyNonce {
+    /// Returns the nonce share `secret`, or `None` if it is zero.
+    pub fn new(secret: Fq) -> Option<Self> {
+        if bool::from(secret.is_zero()) {
+            return None;
+        }
+        Some(TwoPartyNonce {
+            secret,
+            commitment: (Secp256k1::generator() * secret).to_affine(),
+        })
+    }
+
+    /// Returns a uniformly random nonce share.
+    pub fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            if let Some(nonce) = Self::new(Fq::random(&mut rng)) {
+                return nonce;
+            }
+        }
+    }
+
+    /// Returns the commitment $R_i = k_i G$, sent to the other party.
+    pub fn commitment(&self) -> Secp256k1Affine {
+        self.commitment
+    }
+
+    /// Returns the signature value $r$ of the joint nonce point
+    /// $R = k_i R_j$, given the commitment $R_j$ of the other party, and the
+    /// recovery id of $R$.
+    ///
+    /// Returns `None` if $R$ is the identity or $r$ is zero, in which case
+    /// both parties must pick new nonce shares. This is constant time in the
+    /// nonce share when it succeeds.
+    pub fn joint_r(&self, other_commitment: &Secp256k1Affine) -> Option<(Fq, RecoveryId)> {
+        let point = (other_commitment * self.secret).to_affine();
+        let (r, recovery_id) = r_from_point(&point);
+        if bool::from(point.is_identity() | r.is_zero()) {
+            return None;
+        }
+        Some((r, recovery_id))
+    }
+
+    /// Completes the signature of the challenge `z` by the first party,
+    /// given the value $s' = k_2^{-1} (z + r x_1 x_2)$ decrypted from the
+    /// second party's [`PartialSignature`] and the output of
+    /// [`TwoPartyNonce::joint_r`].
+    ///
+    /// Returns $(r, s)$ in low-S form with its recovery id, or `None` if the
+    /// signature does not verify under `joint_public_key`, which is how the
+    /// first party detects a cheating second party.
+    pub fn complete_signature(
+        &self,
+        partial: &Fq,
+        (r, recovery_id): (Fq, RecoveryId),
+        z: &Fq,
+        joint_public_key: &Secp256k1Affine,
+    ) -> Option<(Fq, Fq, RecoveryId)> {
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut nonce_inv = self.secret.invert().unwrap();
+        let s = nonce_inv * partial;
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut nonce_inv);
+
+        let (s, recovery_id) = normalize_s(&s, recovery_id);
+        if !verify_prehashed_with_generator(
+            &Secp256k1::generator(),
+            &joint_public_key.to_curve(),
+            z,
+            &r,
+            &s,
+        ) {
+            return None;
+        }
+        Some((r, s, recovery_id))
+    }
+}
+
+impl fmt::Debug for TwoPartyNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TwoPartyNonce")
+            .field("commitment", &self.commitment)
+            .finish()
+    }
+}
+
+/// The second party's contribution to a two-party signature: the affine
+/// function $s' = a + b x_1$ of the first party's key share, with
+/// $a = k_2^{-1} z$ and $b = k_2^{-1} r x_2$.
+///
+/// In the protocol the second party evaluates it on the first party's
+/// additively homomorphic encryption of $x_1$, adding a random multiple of
+/// $q$ to mask it, and the first party decrypts $s'$ modulo $q$. The
+/// coefficients reveal $k_2$ and $x_2$ to anyone who knows $z$ and $r$, so
+/// they must never be sent in the clear.
+#[derive(Clone, Copy)]
+pub struct PartialSignature {
+    offset: Fq,
+    coefficient: Fq,
+}
+
+impl PartialSignature {
+    /// Returns the constant term $a = k_2^{-1} z$.
+    pub fn offset(&self) -> Fq {
+        self.offset
+    }
+
+    /// Returns the coefficient $b = k_2^{-1} r x_2$ of $x_1$.
+    pub fn coefficient(&self) -> Fq {
+        self.coefficient
+    }
+
+    /// Returns $s' = a + b x_1$ in the clear, the value the first party's
+    /// decryption must give modulo $q$.
+    pub fn evaluate(&self, first_key_share: &Fq) -> Fq {
+        self.offset + self.coefficient * first_key_share
+    }
+}
+
+impl fmt::Debug for PartialSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialSignature").finish()
+    }
+}
+
+/// Splits `secret` into `shares.len()` uniformly random additive shares
+/// that sum to it, writing them to `shares`.
+///
+/// In threshold ECDSA every signer splits its nonce share $k_i$ and its key
+/// share $x_i$ this way to reshare them among a new set of parties. This is
+/// constant time in `secret`.
+///
+/// Panics if `shares` is empty.
+pub fn additive_shares(secret: &Fq, shares: &mut [Fq], mut rng: impl RngCore) {
+    let (last, rest) = shares.split_last_mut().expect("no shares");
+    for share in rest.iter_mut() {
+        *share = Fq::random(&mut rng);
+    }
+    *last = secret - rest.iter().sum::<Fq>();
+}
+
+/// Returns the Lagrange coefficient $\lambda_i$ at zero of the party with
+/// index `index` among the signing parties `indices`, which converts its
+/// Shamir share $x_i = f(i)$ of the key into the additive share
+/// $w_i = \lambda_i x_i$, so that the $w_i$ of the signers sum to $f(0)$.
+///
+/// Returns `None` if `index` is zero, is not in `indices` or if `indices`
+/// repeats an index. This is not constant time: the indices are public.
+pub fn lagrange_coefficient(index: u64, indices: &[u64]) -> Option<Fq> {
+    if index == 0 || indices.iter().filter(|&&j| j == index).count() != 1 {
+        return None;
+    }
+
+    let x_i = Fq::from(index);
+    let mut numerator = Fq::one();
+    let mut denominator = Fq::one();
+    for &j in indices.iter().filter(|&&j| j != index) {
+        let x_j = Fq::from(j);
+        numerator *= x_j;
+        denominator *= x_j - x_i;
+    }
+    Option::<Fq>::from(denominator.invert()).map(|inv| numerator * inv)
+}
+
+/// Returns the mask $\beta'$ and the additive share $\beta = -\beta'$ of
+/// Bob in a multiplicative-to-additive (MtA) conversion of the product
+/// $a b$ of Alice's $a$ and Bob's $b$.
+///
+/// Bob returns $b \cdot \mathrm{Enc}(a) + \mathrm{Enc}(\beta')$ under
+/// Alice's additively homomorphic encryption, and Alice's share is its
+/// decryption $\alpha = a b + \beta'$ modulo $q$, so that
+/// $\alpha + \beta = a b$. The encryption and its range proofs are left to
+/// the caller.
+pub fn mta_mask(rng: impl RngCore) -> (Fq, Fq) {
+    let mask = Fq::random(rng);
+    (mask, -mask)
+}
+
+/// Returns the signature value $r$ of the threshold nonce point
+/// $R = \delta^{-1} \sum_i \Gamma_i$ and the recovery id of $R$, given
+/// the commitments $\Gamma_i = \gamma_i G$ of the signers and their
+/// additive shares $\delta_i$ of $\delta = k \gamma$, so that $R = k^{-1} G$.
+///
+/// Returns `None` if $\delta$ is zero, $R$ is the identity or $r$ is zero,
+/// in which case the signers must start over with new nonces. This is not
+/// constant time: the inputs are broadcast.
+pub fn threshold_r(
+    gamma_commitments: &[Secp256k1],
+    delta_shares: &[Fq],
+) -> Option<(Fq, RecoveryId)> {
+    let delta = delta_shares.iter().sum::<Fq>();
+    let delta_inv = Option::<Fq>::from(delta.invert())?;
+    let gamma = gamma_commitments.iter().sum::<Secp256k1>();
+    let point = (gamma * delta_inv).to_affine();
+    let (r, recovery_id) = r_from_point(&point);
+    if bool::from(point.is_identity() | r.is_zero()) {
+        return None;
+    }
+    Some((r, recovery_id))
+}
+
+/// Returns a signer's share $s_i = z k_i + r \sigma_i$ of the signature
+/// value $s$ of the challenge `z`, given its additive shares $k_i$ of the
+/// nonce and $\sigma_i$ of $k x$. This is constant time in the shares.
+///
+/// Here $k$ is the inverse of the discrete logarithm of $R$, as in GG18, so
+/// $s = \sum_i s_i = k (z + r x)$.
+pub fn threshold_signature_share(nonce_share: &Fq, sigma_share: &Fq, r: &Fq, z: &Fq) -> Fq {
+    z * nonce_share + r * sigma_share
+}
+
+/// Assembles the signature $(r, s)$ of the challenge `z` from the signers'
+/// shares $s_i$, with `r` and `recovery_id` from [`threshold_r`].
+///
+/// Returns $(r, s)$ in low-S form with its recovery id, or `None` if $s$ is
+/// zero or the signature does not verify under `public_key`, which means
+/// that a signer sent a bad share. This is not constant time: the shares are
+/// broadcast.
+pub fn assemble_threshold_signature(
+    s_shares: &[Fq],
+    (r, recovery_id): (Fq, RecoveryId),
+    z: &Fq,
+    public_key: &Secp256k1,
+) -> Option<(Fq, Fq, RecoveryId)> {
+    let s = s_shares.iter().sum::<Fq>();
+    let (s, recovery_id) = normalize_s(&s, recovery_id);
+    if !verify_prehashed_with_generator(&Secp256k1::generator(), public_key, z, &r, &s) {
+        return None;
+    }
+    Some((r, s, recovery_id))
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_recover_reused_nonce() {
+    use group::{Curve, Group, GroupEncoding};
+    use rand::rngs::OsRng;
+
+    let d = Fq::random(OsRng);
+    let k = Fq::random(OsRng);
+    let public_key = Secp256k1::generator() * d;
+
+    let mut x = [0u8; 64];
+    x[..32].copy_from_slice(&(Secp256k1::generator() * k).to_affine().to_bytes().as_ref()[..32]);
+    let r = Fq::from_bytes_wide(&x);
+
+    let sign = |z: Fq| k.invert().unwrap() * (z + r * d);
+    let (z1, z2) = (Fq::random(OsRng), Fq::random(OsRng));
+    let (s1, s2) = (sign(z1), sign(z2));
+
+    assert_eq!(
+        recover_reused_nonce(&public_key, &r, (s1, z1), (s2, z2)),
+        Some((k, d))
+    );
+    assert_eq!(
+        recover_reused_nonce(&public_key, &r, (s1, z1), (-s2, z2)),
+        Some((k, d))
+    );
+    assert_eq!(
+        recover_reused_nonce(&public_key, &r, (-s1, z1), (s2, z2)),
+        Some((-k, d))
+    );
+
+    let other = Secp256k1::random(OsRng);
+    assert_eq!(recover_reused_nonce(&other, &r, (s1, z1), (s2, z2)), None);
+    assert_eq!(
+        recover_reused_nonce(&public_key, &r, (s1, z1), (s1, z1)),
+        None
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_precomputed_verifying_key() {
+    use crate::hazmat::{sign_prehashed_with_nonce, verify_prehashed_with_generator};
+    use rand::rngs::OsRng;
+
+    fn check<const N: usize>(secret_key: &Fq) {
+        let public_key = Secp256k1::generator() * secret_key;
+        let key = PrecomputedVerifyingKey::<N>::new(&public_key);
+        assert_eq!(key.public_key(), public_key.to_affine());
+
+        for _ in 0..4 {
+            let z = Fq::random(OsRng);
+            let (r, s, _) = sign_prehashed_with_nonce(secret_key, &Fq::random(OsRng), &z).unwrap();
+            assert!(key.verify_prehashed(&z, &r, &s));
+            assert!(key.verify_prehashed(&z, &r, &-s));
+
+            for (z, r, s) in [
+                (z + Fq::one(), r, s),
+                (z, r + Fq::one(), s),
+                (z, r, s + Fq::one()),
+                (z, Fq::zero(), s),
+                (z, r, Fq::zero()),
+            ]
+            .iter()
+            {
+                assert!(!key.verify_prehashed(z, r, s));
+                assert!(!verify_prehashed_with_generator(
+                    &Secp256k1::generator(),
+                    &public_key,
+                    z,
+                    r,
+                    s
+                ));
+            }
+        }
+    }
+
+    let secret_key = Fq::random(OsRng);
+    check::<1>(&secret_key);
+    check::<8>(&secret_key);
+    check::<64>(&secret_key);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_verification_batch() {
+    use crate::hazmat::sign_prehashed_with_nonce;
+    use rand::rngs::OsRng;
+
+    assert!(VerificationBatch::new().finalize());
+
+    let mut signatures = Vec::new();
+    for _ in 0..8 {
+        let secret_key = Fq::random(OsRng);
+        let public_key = Secp256k1::generator() * secret_key;
+        let z = Fq::random(OsRng);
+        let (r, s, _) = sign_prehashed_with_nonce(&secret_key, &Fq::random(OsRng), &z).unwrap();
+        signatures.push((z, r, s, public_key));
+    }
+
+    let batch_of = |signatures: &[(Fq, Fq, Fq, Secp256k1)]| {
+        let mut batch = VerificationBatch::new();
+        for (z, r, s, public_key) in signatures.iter() {
+            batch.push(z, r, s, public_key);
+        }
+        batch
+    };
+
+    let batch = batch_of(&signatures);
+    assert_eq!(batch.len(), 8);
+    assert!(!batch.is_empty());
+    assert!(batch.finalize());
+
+    // A high-S signature is accepted, as by the single verification.
+    signatures[0].2 = -signatures[0].2;
+    assert!(batch_of(&signatures).finalize());
+
+    for i in 0..4 {
+        let mut bad = signatures.clone();
+        match i {
+            0 => bad[3].0 += Fq::one(),
+            1 => bad[5].1 = Fq::zero(),
+            2 => bad[7].2 = Fq::zero(),
+            _ => bad[1].3 = signatures[2].3,
+        }
+        assert!(!batch_of(&bad).finalize());
+    }
+}
+
+#[test]
+fn test_eth_v() {
+    let ids = [RecoveryId::new(false, false), RecoveryId::new(true, false)];
+
+    for (i, id) in ids.iter().enumerate() {
+        assert_eq!(id.to_eth_v(None), Some(27 + i as u64));
+        assert_eq!(RecoveryId::from_eth_v(27 + i as u64), Some((*id, None)));
+
+        // Mainnet.
+        assert_eq!(id.to_eth_v(Some(1)), Some(37 + i as u64));
+        assert_eq!(RecoveryId::from_eth_v(37 + i as u64), Some((*id, Some(1))));
+
+        for &chain_id in [0, 56, 137, 1 << 40].iter() {
+            let v = id.to_eth_v(Some(chain_id)).unwrap();
+            assert_eq!(RecoveryId::from_eth_v(v), Some((*id, Some(chain_id))));
+        }
+        assert_eq!(id.to_eth_v(Some(u64::MAX / 2)), None);
+    }
+
+    assert_eq!(RecoveryId::new(false, true).to_eth_v(Some(1)), None);
+    for &v in [0, 1, 26, 29, 34].iter() {
+        assert_eq!(RecoveryId::from_eth_v(v), None);
+    }
+    assert_eq!(RecoveryId::from_byte(4), None);
+    assert_eq!(
+        RecoveryId::from_byte(3).unwrap(),
+        RecoveryId::new(true, true)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_normalize_s() {
+    use rand::rngs::OsRng;
+
+    let id = RecoveryId::new(false, false);
+    let s = Fq::random(OsRng);
+    let (low, low_id) = normalize_s(&s, id);
+    let (high, _) = normalize_s(&-s, id);
+    assert_eq!(low, high);
+    assert!(low <= -low);
+    assert_eq!(low_id.is_y_odd(), low != s);
+    assert_eq!(normalize_s(&low, low_id), (low, low_id));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_two_party_ecdsa() {
+    use crate::hazmat::sign_prehashed_with_nonce;
+    use rand::rngs::OsRng;
+
+    assert!(TwoPartyKeyShare::new(Fq::zero()).is_none());
+    assert!(TwoPartyNonce::new(Fq::zero()).is_none());
+
+    let first = TwoPartyKeyShare::new(Fq::random(OsRng)).unwrap();
+    let second = TwoPartyKeyShare::new(Fq::random(OsRng)).unwrap();
+    let public_key = first.joint_public_key(&second.public_share());
+    assert_eq!(public_key, second.joint_public_key(&first.public_share()));
+    assert_eq!(
+        public_key,
+        (Secp256k1::generator() * (first.secret() * second.secret())).to_affine()
+    );
+
+    for _ in 0..4 {
+        let z = Fq::random(OsRng);
+        let first_nonce = TwoPartyNonce::random(OsRng);
+        let second_nonce = TwoPartyNonce::random(OsRng);
+        let joint_r = first_nonce.joint_r(&second_nonce.commitment()).unwrap();
+        let (r, _) = second_nonce.joint_r(&first_nonce.commitment()).unwrap();
+        assert_eq!(r, joint_r.0);
+
+        let partial = second.partial_sign(&second_nonce, &r, &z);
+        let s_partial = partial.evaluate(&first.secret());
+        let (r, s, recovery_id) = first_nonce
+            .complete_signature(&s_partial, joint_r, &z, &public_key)
+            .unwrap();
+
+        // The same signature as a single signer with x = x1 x2 and
+        // k = k1 k2.
+        let (r_single, s_single, id_single) = sign_prehashed_with_nonce(
+            &(first.secret() * second.secret()),
+            &(first_nonce.secret * second_nonce.secret),
+            &z,
+        )
+        .unwrap();
+        let (s_single, id_single) = normalize_s(&s_single, id_single);
+        assert_eq!((r, s, recovery_id), (r_single, s_single, id_single));
+
+        // A wrong contribution is caught by the first party.
+        assert!(first_nonce
+            .complete_signature(&(s_partial + Fq::one()), joint_r, &z, &public_key)
+            .is_none());
+        assert!(first_nonce
+            .complete_signature(&s_partial, joint_r, &(z + Fq::one()), &public_key)
+            .is_none());
+    }
+
+    assert!(TwoPartyNonce::random(OsRng)
+        .joint_r(&Secp256k1Affine::identity())
+        .is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_threshold_ecdsa() {
+    use crate::hazmat::sign_prehashed_with_nonce;
+    use rand::rngs::OsRng;
+
+    let mut shares = [Fq::zero(); 3];
+    let secret = Fq::random(OsRng);
+    additive_shares(&secret, &mut shares, OsRng);
+    assert_eq!(shares.iter().sum::<Fq>(), secret);
+    let mut single = [Fq::zero()];
+    additive_shares(&secret, &mut single, OsRng);
+    assert_eq!(single[0], secret);
+
+    assert_eq!(lagrange_coefficient(0, &[0, 1]), None);
+    assert_eq!(lagrange_coefficient(2, &[1, 3]), None);
+    assert_eq!(lagrange_coefficient(1, &[1, 1, 3]), None);
+    assert_eq!(lagrange_coefficient(1, &[1]), Some(Fq::one()));
+
+    // A 2-of-3 Shamir sharing x_i = f(i) of x = f(0), signed by parties 1
+    // and 3.
+    let secret_key = Fq::random(OsRng);
+    let slope = Fq::random(OsRng);
+    let public_key = Secp256k1::generator() * secret_key;
+    let signers = [1u64, 3];
+    let key_shares: Vec<Fq> = signers
+        .iter()
+        .map(|&i| (secret_key + slope * Fq::from(i)) * lagrange_coefficient(i, &signers).unwrap())
+        .collect();
+    assert_eq!(key_shares.iter().sum::<Fq>(), secret_key);
+
+    // Shares of the product of the sums of the a_i and b_i: the local terms
+    // plus an MtA conversion of each cross term.
+    let product_shares = |a: &[Fq], b: &[Fq]| -> Vec<Fq> {
+        let mut shares: Vec<Fq> = a.iter().zip(b.iter()).map(|(a, b)| a * b).collect();
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                if i != j {
+                    let (mask, beta) = mta_mask(OsRng);
+                    shares[i] += a[i] * b[j] + mask;
+                    shares[j] += beta;
+                }
+            }
+        }
+        shares
+    };
+
+    for _ in 0..4 {
+        let z = Fq::random(OsRng);
+        let nonces: Vec<Fq> = signers.iter().map(|_| Fq::random(OsRng)).collect();
+        let gamma: Vec<Fq> = signers.iter().map(|_| Fq::random(OsRng)).collect();
+        let delta = product_shares(&nonces, &gamma);
+        let sigma = product_shares(&nonces, &key_shares);
+        let commitments: Vec<Secp256k1> =
+            gamma.iter().map(|g| Secp256k1::generator() * g).collect();
+
+        let joint_r = threshold_r(&commitments, &delta).unwrap();
+        let s_shares: Vec<Fq> = nonces
+            .iter()
+            .zip(sigma.iter())
+            .map(|(nonce, sigma)| threshold_signature_share(nonce, sigma, &joint_r.0, &z))
+            .collect();
+        let signature = assemble_threshold_signature(&s_shares, joint_r, &z, &public_key).unwrap();
+
+        // The same signature as a single signer with the nonce 1 / k.
+        let nonce_sum = nonces.iter().sum::<Fq>();
+        let (r, s, recovery_id) =
+            sign_prehashed_with_nonce(&secret_key, &nonce_sum.invert().unwrap(), &z).unwrap();
+        let (s, recovery_id) = normalize_s(&s, recovery_id);
+        assert_eq!(signature, (r, s, recovery_id));
+
+        let mut bad = s_shares.clone();
+        bad[1] += Fq::one();
+        assert!(assemble_threshold_signature(&bad, joint_r, &z, &public_key).is_none());
+    }
+
+    assert!(threshold_r(&[Secp256k1::generator()], &[Fq::zero()]).is_none());
+}
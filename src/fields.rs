@@ -1,8 +1,96 @@
 //! This module contains implementations for the two finite fields of the Pallas
 //! and Vesta curves.
 
+use core::fmt;
+
 mod fp;
 mod fq;
 
 pub use fp::*;
 pub use fq::*;
+
+/// The error returned when decoding a field element from a byte slice fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldBytesError {
+    /// The slice did not have the length of an encoded field element.
+    InvalidLength {
+        /// The length of an encoded field element.
+        expected: usize,
+        /// The length of the slice.
+        actual: usize,
+    },
+    /// The slice encoded an integer that is not less than the modulus.
+    NonCanonical,
+}
+
+impl fmt::Display for FieldBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldBytesError::InvalidLength { expected, actual } => write!(
+                f,
+                "invalid field element length: expected {} bytes, got {}",
+                expected, actual
+            ),
+            FieldBytesError::NonCanonical => {
+                write!(f, "field element encoding is not less than the modulus")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FieldBytesError {}
+
+/// The error returned when parsing a field element from a string fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseFieldError {
+    /// The string had no digits.
+    Empty,
+    /// The string had a character that is not a digit in the radix.
+    InvalidDigit,
+    /// The integer was not less than the modulus.
+    OutOfRange,
+}
+
+impl fmt::Display for ParseFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseFieldError::Empty => write!(f, "cannot parse field element from empty string"),
+            ParseFieldError::InvalidDigit => write!(f, "invalid digit found in string"),
+            ParseFieldError::OutOfRange => write!(f, "integer is not less than the modulus"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseFieldError {}
+
+/// Parses the digits `digits` in the radix `radix` into little-endian limbs,
+/// failing if the integer does not fit in 256 bits.
+///
+/// Panics if `radix` is not between 2 and 36.
+pub(crate) fn parse_limbs(digits: &str, radix: u32) -> Result<[u64; 4], ParseFieldError> {
+    use crate::arithmetic::mac;
+
+    assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+    if digits.is_empty() {
+        return Err(ParseFieldError::Empty);
+    }
+
+    let mut limbs = [0u64; 4];
+    for c in digits.chars() {
+        let digit = c.to_digit(radix).ok_or(ParseFieldError::InvalidDigit)?;
+
+        // limbs = limbs * radix + digit
+        let mut carry = u64::from(digit);
+        for limb in limbs.iter_mut() {
+            let (l, c) = mac(carry, *limb, u64::from(radix), 0);
+            *limb = l;
+            carry = c;
+        }
+        if carry != 0 {
+            return Err(ParseFieldError::OutOfRange);
+        }
+    }
+    Ok(limbs)
+}
@@ -9,10 +9,7 @@ use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 #[cfg(feature = "bits")]
 use ff::{FieldBits, PrimeFieldBits};
 
-use crate::arithmetic::{adc, mac, sbb};
-
-#[cfg(feature = "std")]
-use pasta_curves::arithmetic::{FieldExt, Group, SqrtRatio};
+use crate::arithmetic::{adc, mac, sbb, FieldExt, Group, SqrtRatio};
 
 /// This represents an element of $\mathbb{F}_p$ where
 ///
@@ -25,81 +22,6 @@ use pasta_curves::arithmetic::{FieldExt, Group, SqrtRatio};
 #[derive(Clone, Copy, Eq)]
 pub struct Fp(pub(crate) [u64; 4]);
 
-impl fmt::Debug for Fp {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let tmp = self.to_repr();
-        write!(f, "0x")?;
-        for &b in tmp.iter().rev() {
-            write!(f, "{:02x}", b)?;
-        }
-        Ok(())
-    }
-}
-
-impl From<bool> for Fp {
-    fn from(bit: bool) -> Fp {
-        if bit {
-            Fp::one()
-        } else {
-            Fp::zero()
-        }
-    }
-}
-
-impl From<u64> for Fp {
-    fn from(val: u64) -> Fp {
-        Fp([val, 0, 0, 0]) * R2
-    }
-}
-
-impl ConstantTimeEq for Fp {
-    fn ct_eq(&self, other: &Self) -> Choice {
-        self.0[0].ct_eq(&other.0[0])
-            & self.0[1].ct_eq(&other.0[1])
-            & self.0[2].ct_eq(&other.0[2])
-            & self.0[3].ct_eq(&other.0[3])
-    }
-}
-
-impl PartialEq for Fp {
-    #[inline]
-    fn eq(&self, other: &Self) -> bool {
-        self.ct_eq(other).unwrap_u8() == 1
-    }
-}
-
-impl core::cmp::Ord for Fp {
-    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        let left = self.to_repr();
-        let right = other.to_repr();
-        left.iter()
-            .zip(right.iter())
-            .rev()
-            .find_map(|(left_byte, right_byte)| match left_byte.cmp(right_byte) {
-                core::cmp::Ordering::Equal => None,
-                res => Some(res),
-            })
-            .unwrap_or(core::cmp::Ordering::Equal)
-    }
-}
-
-impl core::cmp::PartialOrd for Fp {
-    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl ConditionallySelectable for Fp {
-    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-        Fp([
-            u64::conditional_select(&a.0[0], &b.0[0], choice),
-            u64::conditional_select(&a.0[1], &b.0[1], choice),
-            u64::conditional_select(&a.0[2], &b.0[2], choice),
-            u64::conditional_select(&a.0[3], &b.0[3], choice),
-        ])
-    }
-}
-
 /// Constant representing the modulus
 /// p = 0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f
 const MODULUS: Fp = Fp([
@@ -122,54 +44,6 @@ const MODULUS_LIMBS_32: [u32; 8] = [
     0xffff_ffff,
 ];
 
-impl<'a> Neg for &'a Fp {
-    type Output = Fp;
-
-    #[inline]
-    fn neg(self) -> Fp {
-        self.neg()
-    }
-}
-
-impl Neg for Fp {
-    type Output = Fp;
-
-    #[inline]
-    fn neg(self) -> Fp {
-        -&self
-    }
-}
-
-impl<'a, 'b> Sub<&'b Fp> for &'a Fp {
-    type Output = Fp;
-
-    #[inline]
-    fn sub(self, rhs: &'b Fp) -> Fp {
-        self.sub(rhs)
-    }
-}
-
-impl<'a, 'b> Add<&'b Fp> for &'a Fp {
-    type Output = Fp;
-
-    #[inline]
-    fn add(self, rhs: &'b Fp) -> Fp {
-        self.add(rhs)
-    }
-}
-
-impl<'a, 'b> Mul<&'b Fp> for &'a Fp {
-    type Output = Fp;
-
-    #[inline]
-    fn mul(self, rhs: &'b Fp) -> Fp {
-        self.mul(rhs)
-    }
-}
-
-impl_binops_additive!(Fp, Fp);
-impl_binops_multiplicative!(Fp, Fp);
-
 /// INV = -(p^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0xd838091dd2253531;
 
@@ -185,268 +59,79 @@ const R2: Fp = Fp([0x000007a2000e90a1, 0x1, 0, 0]);
 /// 0x100000b73002bb1e33795f671
 const R3: Fp = Fp([0x002bb1e33795f671, 0x100000b73, 0, 0]);
 
-impl Default for Fp {
-    #[inline]
-    fn default() -> Self {
-        Self::zero()
-    }
-}
-
-impl Fp {
-    /// Returns zero, the additive identity.
-    #[inline]
-    pub const fn zero() -> Fp {
-        Fp([0, 0, 0, 0])
-    }
-
-    /// Returns one, the multiplicative identity.
-    #[inline]
-    pub const fn one() -> Fp {
-        R
-    }
-
-    /// Doubles this field element.
-    #[inline]
-    pub const fn double(&self) -> Fp {
-        // TODO: This can be achieved more efficiently with a bitshift.
-        self.add(self)
-    }
-
-    fn from_u512(limbs: [u64; 8]) -> Fp {
-        // We reduce an arbitrary 512-bit number by decomposing it into two 256-bit digits
-        // with the higher bits multiplied by 2^256. Thus, we perform two reductions
-        //
-        // 1. the lower bits are multiplied by R^2, as normal
-        // 2. the upper bits are multiplied by R^2 * 2^256 = R^3
-        //
-        // and computing their sum in the field. It remains to see that arbitrary 256-bit
-        // numbers can be placed into Montgomery form safely using the reduction. The
-        // reduction works so long as the product is less than R=2^256 multiplied by
-        // the modulus. This holds because for any `c` smaller than the modulus, we have
-        // that (2^256 - 1)*c is an acceptable product for the reduction. Therefore, the
-        // reduction always works so long as `c` is in the field; in this case it is either the
-        // constant `R2` or `R3`.
-        let d0 = Fp([limbs[0], limbs[1], limbs[2], limbs[3]]);
-        let d1 = Fp([limbs[4], limbs[5], limbs[6], limbs[7]]);
-        // Convert to Montgomery form
-        d0 * R2 + d1 * R3
-    }
-
-    /// Converts from an integer represented in little endian
-    /// into its (congruent) `Fp` representation.
-    pub const fn from_raw(val: [u64; 4]) -> Self {
-        (&Fp(val)).mul(&R2)
-    }
-
-    /// Squares this element.
-    #[inline]
-    pub const fn square(&self) -> Fp {
-        let (r1, carry) = mac(0, self.0[0], self.0[1], 0);
-        let (r2, carry) = mac(0, self.0[0], self.0[2], carry);
-        let (r3, r4) = mac(0, self.0[0], self.0[3], carry);
-
-        let (r3, carry) = mac(r3, self.0[1], self.0[2], 0);
-        let (r4, r5) = mac(r4, self.0[1], self.0[3], carry);
-
-        let (r5, r6) = mac(r5, self.0[2], self.0[3], 0);
-
-        let r7 = r6 >> 63;
-        let r6 = (r6 << 1) | (r5 >> 63);
-        let r5 = (r5 << 1) | (r4 >> 63);
-        let r4 = (r4 << 1) | (r3 >> 63);
-        let r3 = (r3 << 1) | (r2 >> 63);
-        let r2 = (r2 << 1) | (r1 >> 63);
-        let r1 = r1 << 1;
-
-        let (r0, carry) = mac(0, self.0[0], self.0[0], 0);
-        let (r1, carry) = adc(0, r1, carry);
-        let (r2, carry) = mac(r2, self.0[1], self.0[1], carry);
-        let (r3, carry) = adc(0, r3, carry);
-        let (r4, carry) = mac(r4, self.0[2], self.0[2], carry);
-        let (r5, carry) = adc(0, r5, carry);
-        let (r6, carry) = mac(r6, self.0[3], self.0[3], carry);
-        let (r7, _) = adc(0, r7, carry);
-
-        Fp::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    #[inline(always)]
-    const fn montgomery_reduce(
-        r0: u64,
-        r1: u64,
-        r2: u64,
-        r3: u64,
-        r4: u64,
-        r5: u64,
-        r6: u64,
-        r7: u64,
-    ) -> Self {
-        // The Montgomery reduction here is based on Algorithm 14.32 in
-        // Handbook of Applied Cryptography
-        // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
-
-        let k = r0.wrapping_mul(INV);
-        let (_, carry) = mac(r0, k, MODULUS.0[0], 0);
-        let (r1, carry) = mac(r1, k, MODULUS.0[1], carry);
-        let (r2, carry) = mac(r2, k, MODULUS.0[2], carry);
-        let (r3, carry) = mac(r3, k, MODULUS.0[3], carry);
-        let (r4, carry2) = adc(r4, 0, carry);
-
-        let k = r1.wrapping_mul(INV);
-        let (_, carry) = mac(r1, k, MODULUS.0[0], 0);
-        let (r2, carry) = mac(r2, k, MODULUS.0[1], carry);
-        let (r3, carry) = mac(r3, k, MODULUS.0[2], carry);
-        let (r4, carry) = mac(r4, k, MODULUS.0[3], carry);
-        let (r5, carry2) = adc(r5, carry2, carry);
-
-        let k = r2.wrapping_mul(INV);
-        let (_, carry) = mac(r2, k, MODULUS.0[0], 0);
-        let (r3, carry) = mac(r3, k, MODULUS.0[1], carry);
-        let (r4, carry) = mac(r4, k, MODULUS.0[2], carry);
-        let (r5, carry) = mac(r5, k, MODULUS.0[3], carry);
-        let (r6, carry2) = adc(r6, carry2, carry);
-
-        let k = r3.wrapping_mul(INV);
-        let (_, carry) = mac(r3, k, MODULUS.0[0], 0);
-        let (r4, carry) = mac(r4, k, MODULUS.0[1], carry);
-        let (r5, carry) = mac(r5, k, MODULUS.0[2], carry);
-        let (r6, carry) = mac(r6, k, MODULUS.0[3], carry);
-        let (r7, carry2) = adc(r7, carry2, carry);
-
-        // Result may be within MODULUS of the correct value
-        let (d0, borrow) = sbb(r4, MODULUS.0[0], 0);
-        let (d1, borrow) = sbb(r5, MODULUS.0[1], borrow);
-        let (d2, borrow) = sbb(r6, MODULUS.0[2], borrow);
-        let (d3, borrow) = sbb(r7, MODULUS.0[3], borrow);
-        let (_, borrow) = sbb(carry2, 0, borrow);
-
-        let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-        let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-        let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-        let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-        Fp([d0, d1, d2, d3])
-    }
-
-    /// Multiplies `rhs` by `self`, returning the result.
-    #[inline]
-    pub const fn mul(&self, rhs: &Self) -> Self {
-        // Schoolbook multiplication
-
-        let (r0, carry) = mac(0, self.0[0], rhs.0[0], 0);
-        let (r1, carry) = mac(0, self.0[0], rhs.0[1], carry);
-        let (r2, carry) = mac(0, self.0[0], rhs.0[2], carry);
-        let (r3, r4) = mac(0, self.0[0], rhs.0[3], carry);
-
-        let (r1, carry) = mac(r1, self.0[1], rhs.0[0], 0);
-        let (r2, carry) = mac(r2, self.0[1], rhs.0[1], carry);
-        let (r3, carry) = mac(r3, self.0[1], rhs.0[2], carry);
-        let (r4, r5) = mac(r4, self.0[1], rhs.0[3], carry);
-
-        let (r2, carry) = mac(r2, self.0[2], rhs.0[0], 0);
-        let (r3, carry) = mac(r3, self.0[2], rhs.0[1], carry);
-        let (r4, carry) = mac(r4, self.0[2], rhs.0[2], carry);
-        let (r5, r6) = mac(r5, self.0[2], rhs.0[3], carry);
-
-        let (r3, carry) = mac(r3, self.0[3], rhs.0[0], 0);
-        let (r4, carry) = mac(r4, self.0[3], rhs.0[1], carry);
-        let (r5, carry) = mac(r5, self.0[3], rhs.0[2], carry);
-        let (r6, r7) = mac(r6, self.0[3], rhs.0[3], carry);
-
-        Fp::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
-    }
-
-    /// Subtracts `rhs` from `self`, returning the result.
-    #[inline]
-    pub const fn sub(&self, rhs: &Self) -> Self {
-        let (d0, borrow) = sbb(self.0[0], rhs.0[0], 0);
-        let (d1, borrow) = sbb(self.0[1], rhs.0[1], borrow);
-        let (d2, borrow) = sbb(self.0[2], rhs.0[2], borrow);
-        let (d3, borrow) = sbb(self.0[3], rhs.0[3], borrow);
-
-        // If underflow occurred on the final limb, borrow = 0xfff...fff, otherwise
-        // borrow = 0x000...000. Thus, we use it as a mask to conditionally add the modulus.
-        let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-        let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-        let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-        let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-        Fp([d0, d1, d2, d3])
-    }
-
-    /// Adds `rhs` to `self`, returning the result.
-    #[inline]
-    pub const fn add(&self, rhs: &Self) -> Self {
-        let (d0, carry) = adc(self.0[0], rhs.0[0], 0);
-        let (d1, carry) = adc(self.0[1], rhs.0[1], carry);
-        let (d2, carry) = adc(self.0[2], rhs.0[2], carry);
-        let (d3, carry) = adc(self.0[3], rhs.0[3], carry);
-
-        // Attempt to subtract the modulus, to ensure the value
-        // is smaller than the modulus.
-        let (d0, borrow) = sbb(d0, MODULUS.0[0], 0);
-        let (d1, borrow) = sbb(d1, MODULUS.0[1], borrow);
-        let (d2, borrow) = sbb(d2, MODULUS.0[2], borrow);
-        let (d3, borrow) = sbb(d3, MODULUS.0[3], borrow);
-        let (_, borrow) = sbb(carry, 0, borrow);
-
-        let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-        let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-        let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-        let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-        Fp([d0, d1, d2, d3])
-    }
-
-    /// Negates `self`.
-    #[inline]
-    pub const fn neg(&self) -> Self {
-        // Subtract `self` from `MODULUS` to negate. Ignore the final
-        // borrow because it cannot underflow; self is guaranteed to
-        // be in the field.
-        let (d0, borrow) = sbb(MODULUS.0[0], self.0[0], 0);
-        let (d1, borrow) = sbb(MODULUS.0[1], self.0[1], borrow);
-        let (d2, borrow) = sbb(MODULUS.0[2], self.0[2], borrow);
-        let (d3, _) = sbb(MODULUS.0[3], self.0[3], borrow);
+/// GENERATOR = 3, the smallest generator of the multiplicative group of
+/// order p - 1, which is also a quadratic non-residue
+const GENERATOR: Fp = Fp::from_raw([0x03, 0, 0, 0]);
 
-        // `tmp` could be `MODULUS` if `self` was zero. Create a mask that is
-        // zero if `self` was zero, and `u64::max_value()` if self was nonzero.
-        let mask = (((self.0[0] | self.0[1] | self.0[2] | self.0[3]) == 0) as u64).wrapping_sub(1);
+/// ROOT_OF_UNITY = GENERATOR^t where t * 2^s + 1 = p with t odd. In other
+/// words, this is a 2^s root of unity; as s = 1, it is -1.
+/// 0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e
+const ROOT_OF_UNITY: Fp = Fp::from_raw([
+    0xfffffffefffffc2e,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+]);
 
-        Fp([d0 & mask, d1 & mask, d2 & mask, d3 & mask])
-    }
-}
+#[cfg(feature = "serde")]
+field_serde_impl!(Fp);
+#[cfg(not(feature = "pseudo-mersenne"))]
+field_impl!(Fp, MODULUS, INV, R, R2, R3);
+// p = 2^256 - 0x1000003d1 has all-one upper limbs, as the specialized
+// reduction requires.
+#[cfg(feature = "pseudo-mersenne")]
+static_assertions::const_assert!(
+    MODULUS.0[1] == u64::MAX && MODULUS.0[2] == u64::MAX && MODULUS.0[3] == u64::MAX
+);
+#[cfg(feature = "pseudo-mersenne")]
+field_impl!(
+    Fp,
+    MODULUS,
+    INV,
+    R,
+    R2,
+    R3,
+    montgomery_reduce_pseudo_mersenne
+);
 
-impl From<Fp> for [u8; 32] {
-    fn from(value: Fp) -> [u8; 32] {
-        value.to_repr()
+impl Fp {
+    /// Returns the powers $x_k = \mathit{self}^{2^k - 1}$ for $k = 2$, $22$
+    /// and $223$, from which the addition chains for $p - 2$ and
+    /// $(p + 1) / 4$ start, as both exponents begin with 223 ones.
+    fn pow_by_runs_of_ones(&self) -> (Fp, Fp, Fp) {
+        let sqn = |x: Fp, n: usize| (0..n).fold(x, |x, _| x.square());
+
+        let x1 = *self;
+        let x2 = x1.square() * x1;
+        let x3 = x2.square() * x1;
+        let x6 = sqn(x3, 3) * x3;
+        let x9 = sqn(x6, 3) * x3;
+        let x11 = sqn(x9, 2) * x2;
+        let x22 = sqn(x11, 11) * x11;
+        let x44 = sqn(x22, 22) * x22;
+        let x88 = sqn(x44, 44) * x44;
+        let x176 = sqn(x88, 88) * x88;
+        let x220 = sqn(x176, 44) * x44;
+        let x223 = sqn(x220, 3) * x3;
+        (x2, x22, x223)
     }
-}
 
-impl<'a> From<&'a Fp> for [u8; 32] {
-    fn from(value: &'a Fp) -> [u8; 32] {
-        value.to_repr()
+    /// The GLV endomorphism constant $\beta$, equal to [`FieldExt::ZETA`]:
+    /// a cube root of unity such that $(x, y) \mapsto (\beta x, y)$ maps
+    /// each point of secp256k1 to its multiple by `Fq::ZETA`.
+    pub const BETA: Fp = <Fp as FieldExt>::ZETA;
+
+    /// Multiplies `self` by $3b = 21$, the constant of the complete addition
+    /// formulas for $a = 0$ curves, as $16x + 4x + x$.
+    pub const fn mul_by_3b(&self) -> Fp {
+        let x4 = Fp::double(&Fp::double(self));
+        let x16 = Fp::double(&Fp::double(&x4));
+        Fp::add(&Fp::add(&x16, &x4), self)
     }
 }
 
-#[cfg(feature = "std")]
-impl Group for Fp {
-    type Scalar = Fp;
-
-    fn group_zero() -> Self {
-        Self::zero()
-    }
-    fn group_add(&mut self, rhs: &Self) {
-        *self += *rhs;
-    }
-    fn group_sub(&mut self, rhs: &Self) {
-        *self -= *rhs;
-    }
-    fn group_scale(&mut self, by: &Self::Scalar) {
-        *self *= *by;
-    }
-}
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for Fp {}
 
 impl ff::Field for Fp {
     fn random(mut rng: impl RngCore) -> Self {
@@ -474,19 +159,23 @@ impl ff::Field for Fp {
         self.double()
     }
 
-    #[inline(always)]
+    #[cfg_attr(not(feature = "opt-size"), inline(always))]
     fn square(&self) -> Self {
         self.square()
     }
 
     /// Computes the square root of this element, if it exists.
     fn sqrt(&self) -> CtOption<Self> {
-        let tmp = self.pow(&[
-            0xffffffffbfffff0c,
-            0xffffffffffffffff,
-            0xffffffffffffffff,
-            0x3fffffffffffffff,
-        ]);
+        // p = 3 mod 4, so a square root is self^((p + 1) / 4). This is an
+        // addition chain for the exponent
+        // 0x3fffffffffffffffffffffffffffffffffffffffffffffffffffffffbfffff0c,
+        // which is 223 ones, a zero, 22 ones and then 0b00001100.
+        let sqn = |x: Fp, n: usize| (0..n).fold(x, |x, _| x.square());
+        let (x2, x22, x223) = self.pow_by_runs_of_ones();
+
+        let r = sqn(x223, 23) * x22;
+        let r = sqn(r, 6) * x2;
+        let tmp = sqn(r, 2);
 
         CtOption::new(tmp, tmp.square().ct_eq(self))
     }
@@ -494,12 +183,16 @@ impl ff::Field for Fp {
     /// Computes the multiplicative inverse of this element,
     /// failing if the element is zero.
     fn invert(&self) -> CtOption<Self> {
-        let tmp = self.pow_vartime(&[
-            0xfffffffefffffc2d,
-            0xffffffffffffffff,
-            0xffffffffffffffff,
-            0xffffffffffffffff,
-        ]);
+        // An addition chain for p - 2, which is 223 ones, a zero, 22 ones
+        // and then 0b0000101101. This is constant time; see
+        // `invert_vartime` for a faster variable-time inversion.
+        let sqn = |x: Fp, n: usize| (0..n).fold(x, |x, _| x.square());
+        let (x2, x22, x223) = self.pow_by_runs_of_ones();
+
+        let r = sqn(x223, 23) * x22;
+        let r = sqn(r, 5) * self;
+        let r = sqn(r, 3) * x2;
+        let tmp = sqn(r, 2) * self;
 
         CtOption::new(tmp, !self.ct_eq(&Self::zero()))
     }
@@ -571,15 +264,21 @@ impl ff::PrimeField for Fp {
     }
 
     fn is_odd(&self) -> Choice {
-        Choice::from(self.to_repr()[0] & 1)
+        // Only the low limb of the canonical form is needed, so skip the
+        // byte encoding of `to_repr`.
+        Choice::from((self.to_canonical_limbs()[0] & 1) as u8)
+    }
+
+    fn is_even(&self) -> Choice {
+        !self.is_odd()
     }
 
     fn multiplicative_generator() -> Self {
-        unimplemented!();
+        GENERATOR
     }
 
     fn root_of_unity() -> Self {
-        unimplemented!();
+        ROOT_OF_UNITY
     }
 }
 
@@ -630,36 +329,54 @@ impl PrimeFieldBits for Fp {
     }
 }
 
-#[cfg(feature = "std")]
 impl SqrtRatio for Fp {
-    const T_MINUS1_OVER2: [u64; 4] = [0, 0, 0, 0];
+    /// (t - 1) / 2 = (p - 3) / 4, as S = 1.
+    /// 0x3fffffffffffffffffffffffffffffffffffffffffffffffffffffffbfffff0b
+    const T_MINUS1_OVER2: [u64; 4] = [
+        0xffffffffbfffff0b,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0x3fffffffffffffff,
+    ];
 
     fn pow_by_t_minus1_over2(&self) -> Self {
-        unimplemented!();
+        // An addition chain for (p - 3) / 4, which is 223 ones, a zero, 22
+        // ones and then 0b00001011.
+        let sqn = |x: Fp, n: usize| (0..n).fold(x, |x, _| x.square());
+        let (x2, x22, x223) = self.pow_by_runs_of_ones();
+
+        let r = sqn(x223, 23) * x22;
+        let r = sqn(r, 5) * self;
+        sqn(r, 3) * x2
     }
 
     fn get_lower_32(&self) -> u32 {
-        // TODO: don't reduce, just hash the Montgomery form. (Requires rebuilding perfect hash table.)
+        // The trait asks for the canonical bits, which need the full
+        // reduction. There is no table-based sqrt in this crate whose hash
+        // could be moved to the Montgomery form instead.
         let tmp = Fp::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
 
         tmp.0[0] as u32
     }
 
     fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
-        unimplemented!();
-    }
-
-    fn sqrt_alt(&self) -> (Choice, Self) {
-        unimplemented!();
+        // As p = 3 mod 4, x = num (num div)^((p - 3) / 4) satisfies
+        // x^2 = (num / div) (num div)^((p - 1) / 2), which is num / div if it
+        // is a square and -num / div otherwise, with the non-square G_S = -1.
+        // This needs no inversion, and gives zero if either input is zero.
+        let res = num * (num * div).pow_by_t_minus1_over2();
+        ((res.square() * div).ct_eq(num), res)
     }
 }
 
-#[cfg(feature = "std")]
 impl FieldExt for Fp {
     const MODULUS: &'static str =
         "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
-    const ROOT_OF_UNITY_INV: Self = Self::zero();
-    const DELTA: Self = Self::zero();
+    /// ROOT_OF_UNITY^-1, which is ROOT_OF_UNITY itself since it is -1.
+    const ROOT_OF_UNITY_INV: Self = ROOT_OF_UNITY;
+    /// GENERATOR^(2^S)
+    /// 0x9
+    const DELTA: Self = Self::from_raw([0x09, 0, 0, 0]);
     const TWO_INV: Self = Fp::from_raw([
         0xffffffff7ffffe18,
         0xffffffffffffffff,
@@ -667,31 +384,33 @@ impl FieldExt for Fp {
         0x7fffffffffffffff,
     ]);
 
-    const ZETA: Self = Self::zero();
+    /// The cube root of unity $\beta$ for which the endomorphism
+    /// $(x, y) \mapsto (\beta x, y)$ of secp256k1 acts as multiplication by
+    /// `Fq::ZETA`.
+    /// 0x7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee
+    const ZETA: Self = Self::from_raw([
+        0xc1396c28719501ee,
+        0x9cf0497512f58995,
+        0x6e64479eac3434e9,
+        0x7ae96a2b657c0710,
+    ]);
 
     fn from_u128(v: u128) -> Self {
-        Fp::from_raw([v as u64, (v >> 64) as u64, 0, 0])
+        Fp::from_u128(v)
     }
 
     /// Converts a 512-bit little endian integer into
     /// a `Fp` by reducing by the modulus.
     fn from_bytes_wide(bytes: &[u8; 64]) -> Fp {
-        Fp::from_u512([
-            u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
-            u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
-            u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
-            u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
-            u64::from_le_bytes(bytes[32..40].try_into().unwrap()),
-            u64::from_le_bytes(bytes[40..48].try_into().unwrap()),
-            u64::from_le_bytes(bytes[48..56].try_into().unwrap()),
-            u64::from_le_bytes(bytes[56..64].try_into().unwrap()),
-        ])
+        Fp::from_bytes_wide(bytes)
     }
 
-    fn get_lower_128(&self) -> u128 {
-        let tmp = Fp::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
+    fn pow(&self, by: &[u64; 4]) -> Self {
+        Fp::pow(self, by)
+    }
 
-        u128::from(tmp.0[0]) | (u128::from(tmp.0[1]) << 64)
+    fn get_lower_128(&self) -> u128 {
+        Fp::get_lower_128(self)
     }
 }
 
@@ -729,15 +448,26 @@ fn test_sqrt() {
     // NB: TWO_INV is standing in as a "random" field element
     let v = (Fp::TWO_INV).square().sqrt().unwrap();
     assert!(v == Fp::TWO_INV || (-v) == Fp::TWO_INV);
+
+    assert_eq!(Fp::zero().sqrt().unwrap(), Fp::zero());
+    // -1 is not a square since p = 3 mod 4.
+    assert!(bool::from((-Fp::one()).sqrt().is_none()));
+    let mut a = Fp::from(7);
+    for _ in 0..100 {
+        let root = a.square().sqrt().unwrap();
+        assert!(root == a || root == -a);
+        assert!(bool::from((-a.square()).sqrt().is_none()));
+        a = a.square() + Fp::one();
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 fn fp_to_big(fe: Fp) -> BigUint {
     let u: [u8; 32] = fe.to_repr().into();
     BigUint::from_bytes_le(&u[..])
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 fn big_modulus() -> BigUint {
     let modulus_big = BigUint::from_str_radix(
         "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
@@ -794,6 +524,26 @@ fn test_mul_against_big() {
     }
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_mul_by_small_against_big() {
+    use rand::rngs::OsRng;
+
+    let modulus = &big_modulus();
+    let minus_one = -Fp::one();
+    for &v in [0, 1, 2, u64::MAX].iter() {
+        let c_big = (fp_to_big(minus_one) * BigUint::from(v)) % modulus;
+        assert_eq!(fp_to_big(minus_one.mul_by_u64(v)), c_big);
+    }
+    for _ in 0..1000 {
+        let a = Fp::rand();
+        let v = u128::from(OsRng.next_u64()) << 64 | u128::from(OsRng.next_u64());
+        let c_big = (fp_to_big(a) * BigUint::from(v as u64)) % modulus;
+        assert_eq!(fp_to_big(a.mul_by_u64(v as u64)), c_big);
+        assert_eq!(a.mul_by_u128(v), a * Fp::from_u128(v));
+    }
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_square_against_big() {
@@ -806,3 +556,629 @@ fn test_square_against_big() {
         assert_eq!(c_big_0, c_big_1);
     }
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_invert() {
+    use rand::rngs::OsRng;
+
+    let p_minus_2 = [
+        0xfffffffefffffc2d,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+    ];
+    assert!(bool::from(Fp::zero().invert().is_none()));
+    assert_eq!(Fp::one().invert().unwrap(), Fp::one());
+    for _ in 0..100 {
+        let a = Fp::random(OsRng);
+        let inv = a.invert().unwrap();
+        assert_eq!(inv, a.pow_vartime(&p_minus_2));
+        assert_eq!(inv * a, Fp::one());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_invert_vartime() {
+    use rand::rngs::OsRng;
+
+    assert!(bool::from(Fp::zero().invert_vartime().is_none()));
+    assert_eq!(Fp::one().invert_vartime().unwrap(), Fp::one());
+    assert_eq!((-Fp::one()).invert_vartime().unwrap(), -Fp::one());
+    for _ in 0..100 {
+        let a = Fp::random(OsRng);
+        assert_eq!(a.invert_vartime().unwrap(), a.invert().unwrap());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_le_bits_array() {
+    use rand::rngs::OsRng;
+
+    assert_eq!(Fp::zero().to_le_bits_array(), [false; 256]);
+    let mut one = [false; 256];
+    one[0] = true;
+    assert_eq!(Fp::one().to_le_bits_array(), one);
+
+    for _ in 0..10 {
+        let a = Fp::random(OsRng);
+        let bits = a.to_le_bits_array();
+        let recomposed = bits
+            .iter()
+            .rev()
+            .fold(Fp::zero(), |acc, &bit| acc.double() + Fp::from(bit));
+        assert_eq!(recomposed, a);
+        assert!(a.le_bits_iter().eq(bits.iter().copied()));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_montgomery_limbs() {
+    use rand::rngs::OsRng;
+
+    assert_eq!(Fp::one().to_montgomery_limbs(), R.0);
+    for _ in 0..10 {
+        let a = Fp::random(OsRng);
+        assert_eq!(
+            Fp::from_montgomery_limbs(a.to_montgomery_limbs()).unwrap(),
+            a
+        );
+    }
+    assert!(bool::from(Fp::from_montgomery_limbs(MODULUS.0).is_none()));
+    assert!(bool::from(
+        Fp::from_montgomery_limbs([u64::MAX; 4]).is_none()
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_batch_invert_in_place() {
+    use rand::rngs::OsRng;
+
+    let mut elements = [Fp::zero(); 8];
+    for e in elements.iter_mut() {
+        *e = Fp::random(OsRng);
+    }
+    let mut expected = elements;
+    for e in expected.iter_mut() {
+        *e = e.invert().unwrap();
+    }
+    let mut inverted = elements;
+    assert!(bool::from(Fp::batch_invert_in_place(&mut inverted)));
+    assert_eq!(inverted, expected);
+
+    elements[0] = Fp::zero();
+    elements[5] = Fp::zero();
+    let mut inverted = elements;
+    assert!(!bool::from(Fp::batch_invert_in_place(&mut inverted)));
+    for (a, b) in elements.iter().zip(inverted.iter()) {
+        assert_eq!(*b, a.invert().unwrap_or(Fp::zero()));
+    }
+
+    let mut empty: [Fp; 0] = [];
+    assert!(bool::from(Fp::batch_invert_in_place(&mut empty)));
+}
+
+#[test]
+fn test_legendre() {
+    use crate::arithmetic::LegendreSymbol;
+
+    assert_eq!(Fp::zero().legendre(), LegendreSymbol::Zero);
+    assert!(bool::from(Fp::zero().is_square()));
+    assert_eq!(Fp::one().legendre(), LegendreSymbol::QuadraticResidue);
+
+    let mut num_squares = 0;
+    for i in 1..200u64 {
+        let a = Fp::from(i);
+        assert_eq!(a.square().legendre(), LegendreSymbol::QuadraticResidue);
+        assert!(bool::from(a.square().is_square()));
+
+        let is_square = bool::from(a.is_square());
+        assert_eq!(
+            a.legendre(),
+            if is_square {
+                LegendreSymbol::QuadraticResidue
+            } else {
+                LegendreSymbol::QuadraticNonResidue
+            }
+        );
+        num_squares += is_square as usize;
+    }
+    assert!(num_squares > 50 && num_squares < 150);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_batch_invert() {
+    use rand::rngs::OsRng;
+    use std::vec::Vec;
+
+    for &n in [0, 1, 2, 100].iter() {
+        let mut elements: Vec<Fp> = (0..n).map(|_| Fp::random(OsRng)).collect();
+        let expected: Vec<Fp> = elements.iter().map(|e| e.invert().unwrap()).collect();
+        assert_eq!(Fp::batch_inverted(&elements), expected);
+        assert!(bool::from(Fp::batch_invert(&mut elements)));
+        assert_eq!(elements, expected);
+    }
+
+    let elements = [Fp::from(2), Fp::zero(), Fp::from(3), Fp::zero()];
+    let mut inverted = elements;
+    assert!(!bool::from(Fp::batch_invert(&mut inverted)));
+    for (a, b) in elements.iter().zip(inverted.iter()) {
+        assert_eq!(*b, a.invert().unwrap_or(Fp::zero()));
+    }
+}
+
+#[test]
+fn test_sum_product() {
+    let elements = [Fp::from(2), Fp::from(3), Fp::from(5), -Fp::one()];
+
+    assert_eq!(elements.iter().sum::<Fp>(), Fp::from(9));
+    assert_eq!(elements.iter().cloned().sum::<Fp>(), Fp::from(9));
+    assert_eq!(elements.iter().product::<Fp>(), -Fp::from(30));
+    assert_eq!(elements.iter().cloned().product::<Fp>(), -Fp::from(30));
+
+    assert_eq!(elements[..0].iter().sum::<Fp>(), Fp::zero());
+    assert_eq!(elements[..0].iter().product::<Fp>(), Fp::one());
+}
+
+#[test]
+fn test_ref_ops() {
+    use core::iter::{Product, Sum};
+    use core::ops::{AddAssign, MulAssign, SubAssign};
+
+    // Generic code can rely on every owned and borrowed combination.
+    fn assert_ops<T>()
+    where
+        T: Add<Output = T> + for<'a> Add<&'a T, Output = T>,
+        T: Sub<Output = T> + for<'a> Sub<&'a T, Output = T>,
+        T: Mul<Output = T> + for<'a> Mul<&'a T, Output = T>,
+        T: AddAssign + for<'a> AddAssign<&'a T>,
+        T: SubAssign + for<'a> SubAssign<&'a T>,
+        T: MulAssign + for<'a> MulAssign<&'a T>,
+        T: Neg<Output = T> + Sum + for<'a> Sum<&'a T> + Product + for<'a> Product<&'a T>,
+        for<'a, 'b> &'a T: Add<&'b T, Output = T> + Add<T, Output = T>,
+        for<'a, 'b> &'a T: Sub<&'b T, Output = T> + Sub<T, Output = T>,
+        for<'a, 'b> &'a T: Mul<&'b T, Output = T> + Mul<T, Output = T>,
+        for<'a> &'a T: Neg<Output = T>,
+    {
+    }
+    assert_ops::<Fp>();
+
+    let (a, b) = (Fp::from(6), Fp::from(7));
+    let mut c = a;
+    c += &b;
+    c -= &a;
+    c *= &b;
+    assert_eq!(c, &b * &b);
+    assert_eq!(&a + b, a + &b);
+    assert_eq!(&a - b, -(b - &a));
+    assert_eq!(-&a * b, -Fp::from(42));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_display_hex() {
+    let minus_one = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e";
+    assert_eq!(format!("{}", -Fp::one()), format!("0x{}", minus_one));
+    assert_eq!(format!("{:x}", -Fp::one()), minus_one);
+    assert_eq!(format!("{:#x}", -Fp::one()), format!("0x{}", minus_one));
+    assert_eq!(format!("{:X}", -Fp::one()), minus_one.to_uppercase());
+    assert_eq!(
+        format!("{:#X}", -Fp::one()),
+        format!("0x{}", minus_one.to_uppercase())
+    );
+
+    let five = format!("{:064x}", 5);
+    assert_eq!(format!("{:x}", Fp::from(5)), five);
+    assert_eq!(format!("{}", Fp::from(5)), format!("{:?}", Fp::from(5)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parity() {
+    use rand::rngs::OsRng;
+
+    assert!(bool::from(Fp::zero().is_even()));
+    assert!(bool::from(Fp::one().is_odd()));
+    // The modulus is odd, so -1 is even.
+    assert!(bool::from((-Fp::one()).is_even()));
+    for _ in 0..100 {
+        let a = Fp::random(OsRng);
+        let odd = a.to_repr()[0] & 1 == 1;
+        assert_eq!(bool::from(a.is_odd()), odd);
+        assert_eq!(bool::from(a.is_even()), !odd);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_double() {
+    use rand::rngs::OsRng;
+
+    assert_eq!(Fp::zero().double(), Fp::zero());
+    assert_eq!(Fp::one().double(), Fp::from(2));
+    assert_eq!((-Fp::one()).double(), -Fp::from(2));
+    // 1/2 = (p + 1) / 2 doubles to p + 1, which must be reduced to one.
+    let half = Fp::from(2).invert().unwrap();
+    assert_eq!(half.double(), Fp::one());
+    assert_eq!((half + Fp::one()).double(), Fp::from(3));
+    for _ in 0..100 {
+        let a = Fp::random(OsRng);
+        assert_eq!(a.double(), a + a);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_pow() {
+    use rand::{rngs::OsRng, RngCore};
+
+    let a = Fp::random(OsRng);
+    assert_eq!(a.pow(&[0, 0, 0, 0]), Fp::one());
+    assert_eq!(a.pow(&[1, 0, 0, 0]), a);
+    assert_eq!(a.pow(&[0x11, 0, 0, 0]), a.pow_vartime(&[0x11]));
+    assert_eq!(Fp::zero().pow(&[5, 0, 0, 0]), Fp::zero());
+    assert_eq!(a.pow(&[u64::MAX; 4]), a.pow_vartime(&[u64::MAX; 4]));
+    for _ in 0..10 {
+        let exp = [
+            OsRng.next_u64(),
+            OsRng.next_u64(),
+            OsRng.next_u64(),
+            OsRng.next_u64(),
+        ];
+        assert_eq!(a.pow(&exp), a.pow_vartime(&exp));
+        assert_eq!(FieldExt::pow(&a, &exp), a.pow(&exp));
+    }
+}
+
+#[test]
+fn test_from_u512() {
+    // 2^256 + 5, reduced at compile time.
+    const WIDE: Fp = Fp::from_u512([5, 0, 0, 0, 1, 0, 0, 0]);
+    assert_eq!(WIDE, Fp::from_raw(R.0) + Fp::from(5));
+    assert_eq!(Fp::from_u512([u64::MAX; 8]), Fp::from_raw(R2.0) - Fp::one());
+
+    let mut bytes = [0u8; 64];
+    bytes[0] = 5;
+    bytes[32] = 1;
+    assert_eq!(Fp::from_bytes_wide(&bytes), WIDE);
+    assert_eq!(
+        Fp::from_bytes_wide(&[0xff; 64]),
+        Fp::from_u512([u64::MAX; 8])
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_montgomery_reduce_pseudo_mersenne() {
+    use crate::arithmetic::{montgomery_reduce_pseudo_mersenne, montgomery_reduce_wide};
+    use rand::{rngs::OsRng, RngCore};
+
+    let check = |r: [u64; 8]| {
+        assert_eq!(
+            montgomery_reduce_pseudo_mersenne(&r, &MODULUS.0, INV),
+            montgomery_reduce_wide(&r, &MODULUS.0, INV)
+        );
+    };
+
+    // The largest valid input, p 2^256 - 1.
+    let m = MODULUS.0;
+    check([
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        m[0] - 1,
+        m[1],
+        m[2],
+        m[3],
+    ]);
+    check([0; 8]);
+    check([1, 0, 0, 0, 0, 0, 0, 0]);
+    // The subtraction of k c borrows through the zero limbs.
+    check([u64::MAX, 0, 0, 0, 0, 0, 0, 0]);
+    check([1, 0, u64::MAX, 0, u64::MAX, 0, 0, 0]);
+    check([m[0], m[1], m[2], m[3], 0, 0, 0, 0]);
+    check([0, 0, 0, 0, m[0] - 1, m[1], m[2], m[3]]);
+    for _ in 0..1000 {
+        let mut r = [0u64; 8];
+        for limb in r.iter_mut() {
+            *limb = OsRng.next_u64();
+        }
+        // Keep r below p 2^256.
+        r[7] = r[7].min(m[3] - 1);
+        check(r);
+
+        // Products of field elements, as reduced by `mul`.
+        let (a, b) = (Fp::random(OsRng), Fp::random(OsRng));
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0;
+            for j in 0..4 {
+                let (lo, hi) = mac(wide[i + j], a.0[i], b.0[j], carry);
+                wide[i + j] = lo;
+                carry = hi;
+            }
+            wide[i + 4] = carry;
+        }
+        check(wide);
+    }
+}
+
+#[test]
+fn test_beta() {
+    assert_eq!(Fp::BETA, Fp::ZETA);
+    assert_eq!(Fp::BETA.square() * Fp::BETA, Fp::one());
+    assert!(Fp::BETA != Fp::one());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_beta_endomorphism() {
+    use crate::arithmetic::CurveAffine;
+    use crate::{Fq, Secp256k1, Secp256k1Affine};
+    use core::convert::TryInto;
+    use group::{Curve, Group, GroupEncoding};
+    use rand::rngs::OsRng;
+
+    for _ in 0..10 {
+        let p = Secp256k1::random(OsRng).to_affine();
+        let bytes = p.to_bytes();
+        let x = Fp::from_repr(bytes.as_ref()[..32].try_into().unwrap()).unwrap();
+        let y = Fp::from_repr(bytes.as_ref()[32..].try_into().unwrap()).unwrap();
+
+        let endo = Secp256k1Affine::from_xy(x * Fp::BETA, y).unwrap();
+        assert!(bool::from(endo.is_on_curve()));
+        assert_eq!((p * Fq::ZETA).to_affine(), endo);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_batch_invert_with_zero_mask() {
+    use rand::rngs::OsRng;
+
+    let elements = [
+        Fp::zero(),
+        Fp::random(OsRng),
+        Fp::one(),
+        Fp::zero(),
+        Fp::random(OsRng),
+    ];
+    let mut inverted = elements;
+    let zero_mask = Fp::batch_invert_with_zero_mask(&mut inverted);
+    let zero_mask: std::vec::Vec<bool> = zero_mask.into_iter().map(bool::from).collect();
+    assert_eq!(zero_mask, [true, false, false, true, false]);
+    for (a, b) in elements.iter().zip(inverted.iter()) {
+        assert_eq!(*b, a.invert().unwrap_or(Fp::zero()));
+    }
+
+    assert!(Fp::batch_invert_with_zero_mask(&mut []).is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[cfg(feature = "std")]
+#[test]
+fn test_serde() {
+    use rand::rngs::OsRng;
+
+    for a in [Fp::zero(), Fp::one(), -Fp::one(), Fp::random(OsRng)].iter() {
+        let json = serde_json::to_string(a).unwrap();
+        assert_eq!(json, format!("\"{:?}\"", a));
+        assert_eq!(serde_json::from_str::<Fp>(&json).unwrap(), *a);
+
+        let bytes = bincode::serialize(a).unwrap();
+        assert_eq!(bytes, a.to_repr());
+        assert_eq!(bincode::deserialize::<Fp>(&bytes).unwrap(), *a);
+    }
+
+    // p and every value up to 2^256 - 1 are rejected.
+    for repr in [MODULUS.0, [u64::MAX; 4]].iter() {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_mut(8).zip(repr.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        assert!(bincode::deserialize::<Fp>(&bytes).is_err());
+        let hex: std::string::String = bytes.iter().rev().map(|b| format!("{:02x}", b)).collect();
+        assert!(serde_json::from_str::<Fp>(&format!("\"0x{}\"", hex)).is_err());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_sqrt_ratio() {
+    use rand::rngs::OsRng;
+
+    let p_minus_3_over_4 = Fp::T_MINUS1_OVER2;
+    let a = Fp::random(OsRng);
+    assert_eq!(a.pow_by_t_minus1_over2(), a.pow_vartime(&p_minus_3_over_4));
+
+    for _ in 0..50 {
+        let num = Fp::random(OsRng);
+        let div = Fp::random(OsRng);
+        let (is_square, res) = Fp::sqrt_ratio(&num, &div);
+        let ratio = num * div.invert().unwrap();
+        assert_eq!(bool::from(is_square), bool::from(ratio.is_square()));
+        if bool::from(is_square) {
+            assert_eq!(res.square(), ratio);
+        } else {
+            assert_eq!(res.square(), -ratio);
+        }
+
+        let (is_square, res) = Fp::sqrt_alt(&num);
+        assert_eq!(bool::from(is_square), bool::from(num.sqrt().is_some()));
+        assert_eq!(res.square(), if bool::from(is_square) { num } else { -num });
+    }
+
+    let x = Fp::random(OsRng);
+    let (is_square, res) = Fp::sqrt_ratio(&Fp::zero(), &x);
+    assert!(bool::from(is_square));
+    assert_eq!(res, Fp::zero());
+    assert_eq!(Fp::sqrt_ratio(&Fp::zero(), &Fp::zero()).1, Fp::zero());
+    let (is_square, res) = Fp::sqrt_ratio(&x, &Fp::zero());
+    assert!(!bool::from(is_square));
+    assert_eq!(res, Fp::zero());
+}
+
+#[test]
+fn test_from_str() {
+    use crate::fields::ParseFieldError;
+
+    assert_eq!("0".parse(), Ok(Fp::zero()));
+    assert_eq!("1234567890".parse(), Ok(Fp::from(1234567890)));
+    assert_eq!("0x1f".parse(), Ok(Fp::from(31)));
+    assert_eq!("0x1F".parse(), Ok(Fp::from(31)));
+    assert_eq!(Fp::from_str_radix("101", 2), Ok(Fp::from(5)));
+    assert_eq!(Fp::from_str_radix("z", 36), Ok(Fp::from(35)));
+
+    // p - 1 is the largest element, in both radices.
+    let p_minus_1 =
+        "115792089237316195423570985008687907853269984665640564039457584007908834671662";
+    assert_eq!(p_minus_1.parse(), Ok(-Fp::one()));
+    assert_eq!(Fp::from_str_vartime(p_minus_1), Some(-Fp::one()));
+    assert_eq!(
+        "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e".parse(),
+        Ok(-Fp::one())
+    );
+    assert_eq!(format!("{}", -Fp::one()).parse(), Ok(-Fp::one()));
+
+    let p = "115792089237316195423570985008687907853269984665640564039457584007908834671663";
+    assert_eq!(p.parse::<Fp>(), Err(ParseFieldError::OutOfRange));
+    assert_eq!(
+        "0x10000000000000000000000000000000000000000000000000000000000000000".parse::<Fp>(),
+        Err(ParseFieldError::OutOfRange)
+    );
+    assert_eq!("".parse::<Fp>(), Err(ParseFieldError::Empty));
+    assert_eq!("0x".parse::<Fp>(), Err(ParseFieldError::Empty));
+    assert_eq!("-1".parse::<Fp>(), Err(ParseFieldError::InvalidDigit));
+    assert_eq!("12a".parse::<Fp>(), Err(ParseFieldError::InvalidDigit));
+    assert_eq!("0xg".parse::<Fp>(), Err(ParseFieldError::InvalidDigit));
+}
+
+#[test]
+fn test_bytes_be() {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(Fp::one().to_bytes_be(), one);
+    assert_eq!(Fp::from_repr_be(&one).unwrap(), Fp::one());
+
+    let a = Fp::from_raw([
+        0x0123456789abcdef,
+        0xfedcba9876543210,
+        0x0011223344556677,
+        0x8899aabbccddeeff,
+    ]);
+    let be = a.to_bytes_be();
+    assert_eq!(be[..8], 0x8899aabbccddeeffu64.to_be_bytes());
+    assert_eq!(be[24..], 0x0123456789abcdefu64.to_be_bytes());
+    assert_eq!(Fp::from_repr_be(&be).unwrap(), a);
+    assert_eq!(Fp::from_bytes_be(&be), Ok(a));
+
+    let mut modulus = MODULUS.0;
+    modulus.reverse();
+    let mut be = [0u8; 32];
+    for (bytes, limb) in be.chunks_mut(8).zip(modulus.iter()) {
+        bytes.copy_from_slice(&limb.to_be_bytes());
+    }
+    assert!(bool::from(Fp::from_repr_be(&be).is_none()));
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg(feature = "std")]
+#[test]
+fn test_zeroize() {
+    use rand::rngs::OsRng;
+    use zeroize::Zeroize;
+
+    let mut a = Fp::random(OsRng);
+    a.zeroize();
+    assert_eq!(a, Fp::zero());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_mul_small() {
+    use rand::rngs::OsRng;
+
+    let a = Fp::random(OsRng);
+    assert_eq!(a.mul3(), a * Fp::from(3));
+    assert_eq!(a.mul8(), a * Fp::from(8));
+    assert_eq!(a.mul_by_3b(), a * Fp::from(21));
+    for &k in [0, 1, 2, 7, 21, 0x8000_0000_0000_0001, u64::MAX].iter() {
+        assert_eq!(a.mul_small(k), a.mul_by_u64(k));
+    }
+    assert_eq!((-Fp::one()).mul3(), -Fp::from(3));
+    assert_eq!((-Fp::one()).mul_by_3b(), -Fp::from(21));
+
+    const TWENTY_ONE: Fp = Fp::one().mul_by_3b();
+    assert_eq!(TWENTY_ONE, Fp::from(21));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_root_of_unity() {
+    let t = (big_modulus() - BigUint::from(1u64)) / BigUint::from(1u64 << Fp::S);
+    assert_eq!(
+        Fp::root_of_unity(),
+        Fp::multiplicative_generator().pow_vartime(t.to_u64_digits())
+    );
+    assert_eq!(Fp::root_of_unity().pow_vartime(&[1 << Fp::S]), Fp::one());
+    assert_eq!(Fp::root_of_unity(), -Fp::one());
+    assert_eq!(Fp::root_of_unity() * Fp::ROOT_OF_UNITY_INV, Fp::one());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_generator() {
+    // p - 1 = 2 * 3 * 7 * 13441 * r for a 72-digit prime r, and GENERATOR
+    // has none of the orders (p - 1) / f for these prime factors f.
+    let p_minus_1 = big_modulus() - BigUint::from(1u64);
+    let factors = [
+        "2",
+        "3",
+        "7",
+        "13441",
+        "205115282021455665897114700593932402728804164701536103180137503955397371",
+    ];
+    let mut product = BigUint::from(1u64);
+    for factor in factors.iter() {
+        let factor = BigUint::from_str_radix(factor, 10).unwrap();
+        let exp = &p_minus_1 / &factor;
+        assert!(Fp::multiplicative_generator().pow_vartime(exp.to_u64_digits()) != Fp::one());
+        product = &product * &factor;
+    }
+    assert_eq!(product, p_minus_1);
+    assert_eq!(
+        Fp::multiplicative_generator().pow_vartime(p_minus_1.to_u64_digits()),
+        Fp::one()
+    );
+    assert!(bool::from(!Fp::multiplicative_generator().is_square()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_delta() {
+    assert_eq!(
+        Fp::DELTA,
+        Fp::multiplicative_generator().pow_vartime(&[1u64 << Fp::S])
+    );
+
+    // DELTA generates the subgroup of odd order t, where p - 1 = 2^S * t.
+    let t = (big_modulus() - BigUint::from(1u64)) / BigUint::from(1u64 << Fp::S);
+    assert_eq!(Fp::DELTA.pow_vartime(t.to_u64_digits()), Fp::one());
+    assert!(Fp::DELTA.pow_vartime((t / BigUint::from(3u64)).to_u64_digits()) != Fp::one());
+}
+
+#[test]
+fn test_u128() {
+    let v = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210u128;
+    const V: Fp = Fp::from_u128(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+    assert_eq!(V, Fp::from(v));
+    assert_eq!(V.get_lower_128(), v);
+    assert_eq!(Fp::from_u128(u128::MAX).get_lower_128(), u128::MAX);
+    assert_eq!(V.double().get_lower_128(), v << 1);
+}
@@ -2,20 +2,14 @@ use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
 
-use ff::PrimeField;
+use ff::{Field, PrimeField};
 use rand::RngCore;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-#[cfg(feature = "std")]
-use lazy_static::lazy_static;
-
 #[cfg(feature = "bits")]
 use ff::{FieldBits, PrimeFieldBits};
 
-use crate::arithmetic::{adc, mac, sbb};
-
-#[cfg(feature = "std")]
-use pasta_curves::arithmetic::{FieldExt, Group, SqrtRatio, SqrtTables};
+use crate::arithmetic::{adc, mac, sbb, FieldExt, Group, SqrtRatio};
 
 /// This represents an element of $\mathbb{F}_q$ where
 ///
@@ -28,81 +22,6 @@ use pasta_curves::arithmetic::{FieldExt, Group, SqrtRatio, SqrtTables};
 #[derive(Clone, Copy, Eq)]
 pub struct Fq(pub(crate) [u64; 4]);
 
-impl fmt::Debug for Fq {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let tmp = self.to_repr();
-        write!(f, "0x")?;
-        for &b in tmp.iter().rev() {
-            write!(f, "{:02x}", b)?;
-        }
-        Ok(())
-    }
-}
-
-impl From<bool> for Fq {
-    fn from(bit: bool) -> Fq {
-        if bit {
-            Fq::one()
-        } else {
-            Fq::zero()
-        }
-    }
-}
-
-impl From<u64> for Fq {
-    fn from(val: u64) -> Fq {
-        Fq([val, 0, 0, 0]) * R2
-    }
-}
-
-impl ConstantTimeEq for Fq {
-    fn ct_eq(&self, other: &Self) -> Choice {
-        self.0[0].ct_eq(&other.0[0])
-            & self.0[1].ct_eq(&other.0[1])
-            & self.0[2].ct_eq(&other.0[2])
-            & self.0[3].ct_eq(&other.0[3])
-    }
-}
-
-impl PartialEq for Fq {
-    #[inline]
-    fn eq(&self, other: &Self) -> bool {
-        self.ct_eq(other).unwrap_u8() == 1
-    }
-}
-
-impl core::cmp::Ord for Fq {
-    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        let left = self.to_repr();
-        let right = other.to_repr();
-        left.iter()
-            .zip(right.iter())
-            .rev()
-            .find_map(|(left_byte, right_byte)| match left_byte.cmp(right_byte) {
-                core::cmp::Ordering::Equal => None,
-                res => Some(res),
-            })
-            .unwrap_or(core::cmp::Ordering::Equal)
-    }
-}
-
-impl core::cmp::PartialOrd for Fq {
-    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl ConditionallySelectable for Fq {
-    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-        Fq([
-            u64::conditional_select(&a.0[0], &b.0[0], choice),
-            u64::conditional_select(&a.0[1], &b.0[1], choice),
-            u64::conditional_select(&a.0[2], &b.0[2], choice),
-            u64::conditional_select(&a.0[3], &b.0[3], choice),
-        ])
-    }
-}
-
 /// Constant representing the modulus
 /// q = 0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141
 
@@ -126,54 +45,6 @@ const MODULUS_LIMBS_32: [u32; 8] = [
     0xffff_ffff,
 ];
 
-impl<'a> Neg for &'a Fq {
-    type Output = Fq;
-
-    #[inline]
-    fn neg(self) -> Fq {
-        self.neg()
-    }
-}
-
-impl Neg for Fq {
-    type Output = Fq;
-
-    #[inline]
-    fn neg(self) -> Fq {
-        -&self
-    }
-}
-
-impl<'a, 'b> Sub<&'b Fq> for &'a Fq {
-    type Output = Fq;
-
-    #[inline]
-    fn sub(self, rhs: &'b Fq) -> Fq {
-        self.sub(rhs)
-    }
-}
-
-impl<'a, 'b> Add<&'b Fq> for &'a Fq {
-    type Output = Fq;
-
-    #[inline]
-    fn add(self, rhs: &'b Fq) -> Fq {
-        self.add(rhs)
-    }
-}
-
-impl<'a, 'b> Mul<&'b Fq> for &'a Fq {
-    type Output = Fq;
-
-    #[inline]
-    fn mul(self, rhs: &'b Fq) -> Fq {
-        self.mul(rhs)
-    }
-}
-
-impl_binops_additive!(Fq, Fq);
-impl_binops_multiplicative!(Fq, Fq);
-
 /// INV = -(q^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0x4b0dff665588b13f;
 
@@ -199,269 +70,89 @@ const R3: Fq = Fq([
     0x555d800c18ef116d,
 ]);
 
-impl Default for Fq {
-    #[inline]
-    fn default() -> Self {
-        Self::zero()
-    }
-}
-
-impl Fq {
-    /// Returns zero, the additive identity.
-    #[inline]
-    pub const fn zero() -> Fq {
-        Fq([0, 0, 0, 0])
-    }
-
-    /// Returns one, the multiplicative identity.
-    #[inline]
-    pub const fn one() -> Fq {
-        R
-    }
-
-    /// Doubles this field element.
-    #[inline]
-    pub const fn double(&self) -> Fq {
-        // TODO: This can be achieved more efficiently with a bitshift.
-        self.add(self)
-    }
-
-    fn from_u512(limbs: [u64; 8]) -> Fq {
-        // We reduce an arbitrary 512-bit number by decomposing it into two 256-bit digits
-        // with the higher bits multiplied by 2^256. Thus, we perform two reductions
-        //
-        // 1. the lower bits are multiplied by R^2, as normal
-        // 2. the upper bits are multiplied by R^2 * 2^256 = R^3
-        //
-        // and computing their sum in the field. It remains to see that arbitrary 256-bit
-        // numbers can be placed into Montgomery form safely using the reduction. The
-        // reduction works so long as the product is less than R=2^256 multiplied by
-        // the modulus. This holds because for any `c` smaller than the modulus, we have
-        // that (2^256 - 1)*c is an acceptable product for the reduction. Therefore, the
-        // reduction always works so long as `c` is in the field; in this case it is either the
-        // constant `R2` or `R3`.
-        let d0 = Fq([limbs[0], limbs[1], limbs[2], limbs[3]]);
-        let d1 = Fq([limbs[4], limbs[5], limbs[6], limbs[7]]);
-        // Convert to Montgomery form
-        d0 * R2 + d1 * R3
-    }
-
-    /// Converts from an integer represented in little endian
-    /// into its (congruent) `Fq` representation.
-    pub const fn from_raw(val: [u64; 4]) -> Self {
-        (&Fq(val)).mul(&R2)
-    }
-
-    /// Squares this element.
-    #[inline]
-    pub const fn square(&self) -> Fq {
-        let (r1, carry) = mac(0, self.0[0], self.0[1], 0);
-        let (r2, carry) = mac(0, self.0[0], self.0[2], carry);
-        let (r3, r4) = mac(0, self.0[0], self.0[3], carry);
-
-        let (r3, carry) = mac(r3, self.0[1], self.0[2], 0);
-        let (r4, r5) = mac(r4, self.0[1], self.0[3], carry);
-
-        let (r5, r6) = mac(r5, self.0[2], self.0[3], 0);
-
-        let r7 = r6 >> 63;
-        let r6 = (r6 << 1) | (r5 >> 63);
-        let r5 = (r5 << 1) | (r4 >> 63);
-        let r4 = (r4 << 1) | (r3 >> 63);
-        let r3 = (r3 << 1) | (r2 >> 63);
-        let r2 = (r2 << 1) | (r1 >> 63);
-        let r1 = r1 << 1;
-
-        let (r0, carry) = mac(0, self.0[0], self.0[0], 0);
-        let (r1, carry) = adc(0, r1, carry);
-        let (r2, carry) = mac(r2, self.0[1], self.0[1], carry);
-        let (r3, carry) = adc(0, r3, carry);
-        let (r4, carry) = mac(r4, self.0[2], self.0[2], carry);
-        let (r5, carry) = adc(0, r5, carry);
-        let (r6, carry) = mac(r6, self.0[3], self.0[3], carry);
-        let (r7, _) = adc(0, r7, carry);
-
-        Fq::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    #[inline(always)]
-    const fn montgomery_reduce(
-        r0: u64,
-        r1: u64,
-        r2: u64,
-        r3: u64,
-        r4: u64,
-        r5: u64,
-        r6: u64,
-        r7: u64,
-    ) -> Self {
-        // The Montgomery reduction here is based on Algorithm 14.32 in
-        // Handbook of Applied Cryptography
-        // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
-
-        let k = r0.wrapping_mul(INV);
-        let (_, carry) = mac(r0, k, MODULUS.0[0], 0);
-        let (r1, carry) = mac(r1, k, MODULUS.0[1], carry);
-        let (r2, carry) = mac(r2, k, MODULUS.0[2], carry);
-        let (r3, carry) = mac(r3, k, MODULUS.0[3], carry);
-        let (r4, carry2) = adc(r4, 0, carry);
-
-        let k = r1.wrapping_mul(INV);
-        let (_, carry) = mac(r1, k, MODULUS.0[0], 0);
-        let (r2, carry) = mac(r2, k, MODULUS.0[1], carry);
-        let (r3, carry) = mac(r3, k, MODULUS.0[2], carry);
-        let (r4, carry) = mac(r4, k, MODULUS.0[3], carry);
-        let (r5, carry2) = adc(r5, carry2, carry);
-
-        let k = r2.wrapping_mul(INV);
-        let (_, carry) = mac(r2, k, MODULUS.0[0], 0);
-        let (r3, carry) = mac(r3, k, MODULUS.0[1], carry);
-        let (r4, carry) = mac(r4, k, MODULUS.0[2], carry);
-        let (r5, carry) = mac(r5, k, MODULUS.0[3], carry);
-        let (r6, carry2) = adc(r6, carry2, carry);
-
-        let k = r3.wrapping_mul(INV);
-        let (_, carry) = mac(r3, k, MODULUS.0[0], 0);
-        let (r4, carry) = mac(r4, k, MODULUS.0[1], carry);
-        let (r5, carry) = mac(r5, k, MODULUS.0[2], carry);
-        let (r6, carry) = mac(r6, k, MODULUS.0[3], carry);
-        let (r7, carry2) = adc(r7, carry2, carry);
-
-        // Result may be within MODULUS of the correct value
-        let (d0, borrow) = sbb(r4, MODULUS.0[0], 0);
-        let (d1, borrow) = sbb(r5, MODULUS.0[1], borrow);
-        let (d2, borrow) = sbb(r6, MODULUS.0[2], borrow);
-        let (d3, borrow) = sbb(r7, MODULUS.0[3], borrow);
-        let (_, borrow) = sbb(carry2, 0, borrow);
-
-        let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-        let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-        let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-        let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-        Fq([d0, d1, d2, d3])
-    }
-
-    /// Multiplies `rhs` by `self`, returning the result.
-    #[inline]
-    pub const fn mul(&self, rhs: &Self) -> Self {
-        // Schoolbook multiplication
-
-        let (r0, carry) = mac(0, self.0[0], rhs.0[0], 0);
-        let (r1, carry) = mac(0, self.0[0], rhs.0[1], carry);
-        let (r2, carry) = mac(0, self.0[0], rhs.0[2], carry);
-        let (r3, r4) = mac(0, self.0[0], rhs.0[3], carry);
-
-        let (r1, carry) = mac(r1, self.0[1], rhs.0[0], 0);
-        let (r2, carry) = mac(r2, self.0[1], rhs.0[1], carry);
-        let (r3, carry) = mac(r3, self.0[1], rhs.0[2], carry);
-        let (r4, r5) = mac(r4, self.0[1], rhs.0[3], carry);
-
-        let (r2, carry) = mac(r2, self.0[2], rhs.0[0], 0);
-        let (r3, carry) = mac(r3, self.0[2], rhs.0[1], carry);
-        let (r4, carry) = mac(r4, self.0[2], rhs.0[2], carry);
-        let (r5, r6) = mac(r5, self.0[2], rhs.0[3], carry);
-
-        let (r3, carry) = mac(r3, self.0[3], rhs.0[0], 0);
-        let (r4, carry) = mac(r4, self.0[3], rhs.0[1], carry);
-        let (r5, carry) = mac(r5, self.0[3], rhs.0[2], carry);
-        let (r6, r7) = mac(r6, self.0[3], rhs.0[3], carry);
-
-        Fq::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
-    }
-
-    /// Subtracts `rhs` from `self`, returning the result.
-    #[inline]
-    pub const fn sub(&self, rhs: &Self) -> Self {
-        let (d0, borrow) = sbb(self.0[0], rhs.0[0], 0);
-        let (d1, borrow) = sbb(self.0[1], rhs.0[1], borrow);
-        let (d2, borrow) = sbb(self.0[2], rhs.0[2], borrow);
-        let (d3, borrow) = sbb(self.0[3], rhs.0[3], borrow);
-
-        // If underflow occurred on the final limb, borrow = 0xfff...fff, otherwise
-        // borrow = 0x000...000. Thus, we use it as a mask to conditionally add the modulus.
-        let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-        let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-        let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-        let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
-
-        Fq([d0, d1, d2, d3])
-    }
-
-    /// Adds `rhs` to `self`, returning the result.
-    #[inline]
-    pub const fn add(&self, rhs: &Self) -> Self {
-        let (d0, carry) = adc(self.0[0], rhs.0[0], 0);
-        let (d1, carry) = adc(self.0[1], rhs.0[1], carry);
-        let (d2, carry) = adc(self.0[2], rhs.0[2], carry);
-        let (d3, carry) = adc(self.0[3], rhs.0[3], carry);
-
-        // Attempt to subtract the modulus, to ensure the value
-        // is smaller than the modulus.
-        let (d0, borrow) = sbb(d0, MODULUS.0[0], 0);
-        let (d1, borrow) = sbb(d1, MODULUS.0[1], borrow);
-        let (d2, borrow) = sbb(d2, MODULUS.0[2], borrow);
-        let (d3, borrow) = sbb(d3, MODULUS.0[3], borrow);
-        let (_, borrow) = sbb(carry, 0, borrow);
+/// GENERATOR = 7, the smallest generator of the multiplicative group of
+/// order q - 1, which is also a quadratic non-residue
+const GENERATOR: Fq = Fq::from_raw([0x07, 0, 0, 0]);
+
+/// ROOT_OF_UNITY = GENERATOR^t where t * 2^s + 1 = q with t odd. In other
+/// words, this is a 2^s root of unity.
+/// 0x0c1dc060e7a91986df9879a3fbc483a898bdeab680756045992f4b5402b052f2
+const ROOT_OF_UNITY: Fq = Fq::from_raw([
+    0x992f4b5402b052f2,
+    0x98bdeab680756045,
+    0xdf9879a3fbc483a8,
+    0x0c1dc060e7a91986,
+]);
 
-        let (d0, carry) = adc(d0, MODULUS.0[0] & borrow, 0);
-        let (d1, carry) = adc(d1, MODULUS.0[1] & borrow, carry);
-        let (d2, carry) = adc(d2, MODULUS.0[2] & borrow, carry);
-        let (d3, _) = adc(d3, MODULUS.0[3] & borrow, carry);
+/// ROOT_OF_UNITY^-1
+/// 0xfd3ae181f12d7096efc7b0c75b8cbb7277a275910aa413c3b6fb30a0884f0d1c
+const ROOT_OF_UNITY_INV: Fq = Fq::from_raw([
+    0xb6fb30a0884f0d1c,
+    0x77a275910aa413c3,
+    0xefc7b0c75b8cbb72,
+    0xfd3ae181f12d7096,
+]);
 
-        Fq([d0, d1, d2, d3])
-    }
+field_impl!(Fq, MODULUS, INV, R, R2, R3);
+#[cfg(feature = "serde")]
+field_serde_impl!(Fq);
 
-    /// Negates `self`.
-    #[inline]
-    pub const fn neg(&self) -> Self {
-        // Subtract `self` from `MODULUS` to negate. Ignore the final
-        // borrow because it cannot underflow; self is guaranteed to
-        // be in the field.
-        let (d0, borrow) = sbb(MODULUS.0[0], self.0[0], 0);
-        let (d1, borrow) = sbb(MODULUS.0[1], self.0[1], borrow);
-        let (d2, borrow) = sbb(MODULUS.0[2], self.0[2], borrow);
-        let (d3, _) = sbb(MODULUS.0[3], self.0[3], borrow);
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for Fq {}
 
-        // `tmp` could be `MODULUS` if `self` was zero. Create a mask that is
-        // zero if `self` was zero, and `u64::max_value()` if self was nonzero.
-        let mask = (((self.0[0] | self.0[1] | self.0[2] | self.0[3]) == 0) as u64).wrapping_sub(1);
+/// A scalar holding a secret such as a private key or a nonce, which is
+/// zeroized when dropped.
+///
+/// Unlike [`Fq`] it is not `Copy`, so that the secret is not left behind in
+/// implicit copies, and its `Debug` implementation does not print the value.
+#[cfg(feature = "zeroize")]
+#[derive(Clone)]
+pub struct SecretFq(Fq);
+
+#[cfg(feature = "zeroize")]
+impl SecretFq {
+    /// Wraps the secret scalar `secret`.
+    pub fn new(secret: Fq) -> Self {
+        SecretFq(secret)
+    }
 
-        Fq([d0 & mask, d1 & mask, d2 & mask, d3 & mask])
+    /// Returns a reference to the secret scalar.
+    pub fn expose_secret(&self) -> &Fq {
+        &self.0
     }
 }
 
-impl From<Fq> for [u8; 32] {
-    fn from(value: Fq) -> [u8; 32] {
-        value.to_repr()
+#[cfg(feature = "zeroize")]
+impl From<Fq> for SecretFq {
+    fn from(secret: Fq) -> Self {
+        SecretFq(secret)
     }
 }
 
-impl<'a> From<&'a Fq> for [u8; 32] {
-    fn from(value: &'a Fq) -> [u8; 32] {
-        value.to_repr()
+#[cfg(feature = "zeroize")]
+impl fmt::Debug for SecretFq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SecretFq(..)")
     }
 }
 
-#[cfg(feature = "std")]
-impl Group for Fq {
-    type Scalar = Fq;
-
-    fn group_zero() -> Self {
-        Self::zero()
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretFq {
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
     }
-    fn group_add(&mut self, rhs: &Self) {
-        *self += *rhs;
-    }
-    fn group_sub(&mut self, rhs: &Self) {
-        *self -= *rhs;
-    }
-    fn group_scale(&mut self, by: &Self::Scalar) {
-        *self *= *by;
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretFq {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretFq {}
+
 impl ff::Field for Fq {
     fn random(mut rng: impl RngCore) -> Self {
         Self::from_u512([
@@ -488,34 +179,76 @@ impl ff::Field for Fq {
         self.double()
     }
 
-    #[inline(always)]
+    #[cfg_attr(not(feature = "opt-size"), inline(always))]
     fn square(&self) -> Self {
         self.square()
     }
 
     /// Computes the square root of this element, if it exists.
     fn sqrt(&self) -> CtOption<Self> {
-        #[cfg(feature = "std")]
-        {
-            let (is_square, res) = FQ_TABLES.sqrt_alt(self);
-            CtOption::new(res, is_square)
-        }
-
-        #[cfg(not(feature = "std"))]
-        crate::arithmetic::sqrt_tonelli_shanks(self, &T_MINUS1_OVER2)
+        crate::arithmetic::sqrt_tonelli_shanks_with_w(self, self.pow_by_t_minus1_over2())
     }
 
     /// Computes the multiplicative inverse of this element,
     /// failing if the element is zero.
     fn invert(&self) -> CtOption<Self> {
-        let tmp = self.pow_vartime(&[
-            0xbfd25e8cd036413f,
-            0xbaaedce6af48a03b,
-            0xfffffffffffffffe,
-            0xffffffffffffffff,
-        ]);
-
-        CtOption::new(tmp, !self.ct_eq(&Self::zero()))
+        // An addition chain for q - 2, which is 127 ones followed by 129
+        // bits: the ones are built from the powers x_k = self^(2^k - 1), and
+        // the rest with a sliding window over the odd powers u_k = self^k for
+        // k < 16. The sequence of operations does not depend on `self`.
+        let sqn = |x: Fq, n: usize| (0..n).fold(x, |x, _| x.square());
+
+        let x1 = *self;
+        let x2 = x1.square() * x1;
+        let x3 = x2.square() * x1;
+        let x6 = sqn(x3, 3) * x3;
+        let x12 = sqn(x6, 6) * x6;
+        let x24 = sqn(x12, 12) * x12;
+        let x48 = sqn(x24, 24) * x24;
+        let x96 = sqn(x48, 48) * x48;
+        let x120 = sqn(x96, 24) * x24;
+        let x126 = sqn(x120, 6) * x6;
+        let x127 = sqn(x126, 1) * x1;
+
+        let u1 = x1;
+        let u2 = x1.square();
+        let u3 = x2;
+        let u5 = u3 * u2;
+        let u7 = u5 * u2;
+        let u9 = u7 * u2;
+        let u11 = u9 * u2;
+        let u13 = u11 * u2;
+        let u15 = u13 * u2;
+
+        let mut r = x127;
+        r = sqn(r, 5) * u11;
+        r = sqn(r, 3) * u5;
+        r = sqn(r, 4) * u5;
+        r = sqn(r, 4) * u7;
+        r = sqn(r, 5) * u13;
+        r = sqn(r, 2) * u3;
+        r = sqn(r, 5) * u7;
+        r = sqn(r, 6) * u13;
+        r = sqn(r, 5) * u11;
+        r = sqn(r, 4) * u13;
+        r = sqn(r, 3) * u1;
+        r = sqn(r, 6) * u5;
+        r = sqn(r, 10) * u7;
+        r = sqn(r, 4) * u7;
+        r = sqn(r, 5) * u15;
+        r = sqn(r, 4) * u15;
+        r = sqn(r, 5) * u9;
+        r = sqn(r, 6) * u11;
+        r = sqn(r, 4) * u13;
+        r = sqn(r, 5) * u3;
+        r = sqn(r, 6) * u13;
+        r = sqn(r, 10) * u13;
+        r = sqn(r, 4) * u9;
+        r = sqn(r, 9) * u9;
+        r = sqn(r, 4) * u15;
+        let res = sqn(r, 1) * u1;
+
+        CtOption::new(res, !self.ct_eq(&Self::zero()))
     }
 
     fn pow_vartime<S: AsRef<[u64]>>(&self, exp: S) -> Self {
@@ -585,15 +318,21 @@ impl ff::PrimeField for Fq {
     }
 
     fn is_odd(&self) -> Choice {
-        Choice::from(self.to_repr()[0] & 1)
+        // Only the low limb of the canonical form is needed, so skip the
+        // byte encoding of `to_repr`.
+        Choice::from((self.to_canonical_limbs()[0] & 1) as u8)
+    }
+
+    fn is_even(&self) -> Choice {
+        !self.is_odd()
     }
 
     fn multiplicative_generator() -> Self {
-        unimplemented!();
+        GENERATOR
     }
 
     fn root_of_unity() -> Self {
-        unimplemented!();
+        ROOT_OF_UNITY
     }
 }
 
@@ -644,42 +383,112 @@ impl PrimeFieldBits for Fq {
     }
 }
 
-#[cfg(feature = "std")]
-lazy_static! {
-    // The perfect hash parameters are found by `squareroottab.sage` in zcash/pasta.
-    static ref FQ_TABLES: SqrtTables<Fq> = SqrtTables::new(0x116A9E, 1206);
-}
-
-#[cfg(feature = "std")]
 impl SqrtRatio for Fq {
-    const T_MINUS1_OVER2: [u64; 4] = [0, 0, 0, 0];
+    const T_MINUS1_OVER2: [u64; 4] = [
+        0x777fa4bd19a06c82,
+        0xfd755db9cd5e9140,
+        0xffffffffffffffff,
+        0x01ffffffffffffff,
+    ];
 
     fn pow_by_t_minus1_over2(&self) -> Self {
-        unimplemented!()
+        // An addition chain for (t - 1) / 2, which is 127 ones followed by
+        // 122 bits: the ones are built from the powers x_k = self^(2^k - 1),
+        // and the rest with a sliding window over the odd powers u_k = self^k
+        // for k < 16.
+        let sqn = |x: Fq, n: usize| (0..n).fold(x, |x, _| x.square());
+
+        let x1 = *self;
+        let x2 = x1.square() * x1;
+        let x3 = x2.square() * x1;
+        let x6 = sqn(x3, 3) * x3;
+        let x12 = sqn(x6, 6) * x6;
+        let x24 = sqn(x12, 12) * x12;
+        let x48 = sqn(x24, 24) * x24;
+        let x96 = sqn(x48, 48) * x48;
+        let x120 = sqn(x96, 24) * x24;
+        let x126 = sqn(x120, 6) * x6;
+        let x127 = sqn(x126, 1) * x1;
+
+        let u1 = x1;
+        let u2 = x1.square();
+        let u3 = x2;
+        let u5 = u3 * u2;
+        let u7 = u5 * u2;
+        let u9 = u7 * u2;
+        let u11 = u9 * u2;
+        let u13 = u11 * u2;
+        let u15 = u13 * u2;
+
+        let mut r = x127;
+        r = sqn(r, 5) * u11;
+        r = sqn(r, 3) * u5;
+        r = sqn(r, 4) * u5;
+        r = sqn(r, 4) * u7;
+        r = sqn(r, 5) * u13;
+        r = sqn(r, 2) * u3;
+        r = sqn(r, 5) * u7;
+        r = sqn(r, 6) * u13;
+        r = sqn(r, 5) * u11;
+        r = sqn(r, 4) * u13;
+        r = sqn(r, 3) * u1;
+        r = sqn(r, 6) * u5;
+        r = sqn(r, 10) * u7;
+        r = sqn(r, 4) * u7;
+        r = sqn(r, 5) * u15;
+        r = sqn(r, 4) * u15;
+        r = sqn(r, 5) * u9;
+        r = sqn(r, 6) * u11;
+        r = sqn(r, 4) * u13;
+        r = sqn(r, 5) * u3;
+        r = sqn(r, 6) * u13;
+        r = sqn(r, 10) * u13;
+        r = sqn(r, 4) * u9;
+        r = sqn(r, 6) * u1;
+        sqn(r, 1)
     }
 
     fn get_lower_32(&self) -> u32 {
-        // TODO: don't reduce, just hash the Montgomery form. (Requires rebuilding perfect hash table.)
+        // The trait asks for the canonical bits, which need the full
+        // reduction. There is no table-based sqrt in this crate whose hash
+        // could be moved to the Montgomery form instead: sqrt uses the
+        // `pow_by_t_minus1_over2` addition chain.
         let tmp = Fq::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
 
         tmp.0[0] as u32
     }
 
     fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
-        FQ_TABLES.sqrt_ratio(num, div)
-    }
-
-    fn sqrt_alt(&self) -> (Choice, Self) {
-        FQ_TABLES.sqrt_alt(self)
+        let ratio = num * div.invert().unwrap_or_else(Self::zero);
+
+        // The 2^S-th root of unity is a non-square, so exactly one of `ratio`
+        // and `ratio * root_of_unity` has a square root when `ratio` is nonzero.
+        let sqrt = ratio.sqrt();
+        let sqrt_alt = (ratio * Self::root_of_unity()).sqrt();
+        let res = Self::conditional_select(
+            &sqrt_alt.unwrap_or_else(Self::zero),
+            &sqrt.unwrap_or_else(Self::zero),
+            sqrt.is_some(),
+        );
+
+        let div_is_zero = div.ct_eq(&Self::zero());
+        let num_is_zero = num.ct_eq(&Self::zero());
+        (sqrt.is_some() & !(div_is_zero & !num_is_zero), res)
     }
 }
 
-#[cfg(feature = "std")]
 impl FieldExt for Fq {
     const MODULUS: &'static str =
         "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
-    const ROOT_OF_UNITY_INV: Self = Self::zero();
-    const DELTA: Self = Self::zero();
+    const ROOT_OF_UNITY_INV: Self = ROOT_OF_UNITY_INV;
+    /// GENERATOR^(2^S)
+    /// 0xcbc21fe4561c8d63b78e780e1341e199417c8c0bb7601
+    const DELTA: Self = Self::from_raw([
+        0x199417c8c0bb7601,
+        0xd63b78e780e1341e,
+        0x000cbc21fe4561c8,
+        0,
+    ]);
     const TWO_INV: Self = Self::from_raw([
         0xdfe92f46681b20a1,
         0x5d576e7357a4501d,
@@ -687,36 +496,34 @@ impl FieldExt for Fq {
         0x7fffffffffffffff,
     ]);
 
-    const ZETA: Self = Self::zero();
+    /// The cube root of unity $\lambda$ for which the endomorphism
+    /// $(x, y) \mapsto (\beta x, y)$ of secp256k1 acts as multiplication by
+    /// $\lambda$.
+    /// 0x5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72
+    const ZETA: Self = Self::from_raw([
+        0xdf02967c1b23bd72,
+        0x122e22ea20816678,
+        0xa5261c028812645a,
+        0x5363ad4cc05c30e0,
+    ]);
 
     fn from_u128(v: u128) -> Self {
-        Fq::from_raw([v as u64, (v >> 64) as u64, 0, 0])
+        Fq::from_u128(v)
     }
 
-    /// Converts a 512-bit little endian integer into
-    /// a `Fq` by reducing by the modulus.
     fn from_bytes_wide(bytes: &[u8; 64]) -> Fq {
-        Fq::from_u512([
-            u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
-            u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
-            u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
-            u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
-            u64::from_le_bytes(bytes[32..40].try_into().unwrap()),
-            u64::from_le_bytes(bytes[40..48].try_into().unwrap()),
-            u64::from_le_bytes(bytes[48..56].try_into().unwrap()),
-            u64::from_le_bytes(bytes[56..64].try_into().unwrap()),
-        ])
+        Fq::from_bytes_wide(bytes)
     }
 
-    fn get_lower_128(&self) -> u128 {
-        let tmp = Fq::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0);
+    fn pow(&self, by: &[u64; 4]) -> Self {
+        Fq::pow(self, by)
+    }
 
-        u128::from(tmp.0[0]) | (u128::from(tmp.0[1]) << 64)
+    fn get_lower_128(&self) -> u128 {
+        Fq::get_lower_128(self)
     }
 }
 
-#[cfg(all(test, feature = "std"))]
-use ff::Field;
 #[cfg(all(test, feature = "std"))]
 use num_bigint::BigUint;
 #[cfg(all(test, feature = "std"))]
@@ -743,13 +550,13 @@ fn test_inv_2() {
     assert_eq!(Fq::TWO_INV, Fq::from(2).invert().unwrap());
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 fn fp_to_big(fe: Fq) -> BigUint {
     let u: [u8; 32] = fe.to_repr().into();
     BigUint::from_bytes_le(&u[..])
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 fn big_modulus() -> BigUint {
     let modulus_big = BigUint::from_str_radix(
         "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
@@ -806,6 +613,26 @@ fn test_mul_against_big() {
     }
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_mul_by_small_against_big() {
+    use rand::rngs::OsRng;
+
+    let modulus = &big_modulus();
+    let minus_one = -Fq::one();
+    for &v in [0, 1, 2, u64::MAX].iter() {
+        let c_big = (fp_to_big(minus_one) * BigUint::from(v)) % modulus;
+        assert_eq!(fp_to_big(minus_one.mul_by_u64(v)), c_big);
+    }
+    for _ in 0..1000 {
+        let a = Fq::rand();
+        let v = u128::from(OsRng.next_u64()) << 64 | u128::from(OsRng.next_u64());
+        let c_big = (fp_to_big(a) * BigUint::from(v as u64)) % modulus;
+        assert_eq!(fp_to_big(a.mul_by_u64(v as u64)), c_big);
+        assert_eq!(a.mul_by_u128(v), a * Fq::from_u128(v));
+    }
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_square_against_big() {
@@ -818,3 +645,644 @@ fn test_square_against_big() {
         assert_eq!(c_big_0, c_big_1);
     }
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_invert() {
+    use rand::rngs::OsRng;
+
+    let q_minus_2 = [
+        0xbfd25e8cd036413f,
+        0xbaaedce6af48a03b,
+        0xfffffffffffffffe,
+        0xffffffffffffffff,
+    ];
+    assert!(bool::from(Fq::zero().invert().is_none()));
+    assert_eq!(Fq::one().invert().unwrap(), Fq::one());
+    for _ in 0..100 {
+        let a = Fq::random(OsRng);
+        let inv = a.invert().unwrap();
+        assert_eq!(inv, a.pow_vartime(&q_minus_2));
+        assert_eq!(inv * a, Fq::one());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_invert_vartime() {
+    use rand::rngs::OsRng;
+
+    assert!(bool::from(Fq::zero().invert_vartime().is_none()));
+    assert_eq!(Fq::one().invert_vartime().unwrap(), Fq::one());
+    assert_eq!((-Fq::one()).invert_vartime().unwrap(), -Fq::one());
+    for _ in 0..100 {
+        let a = Fq::random(OsRng);
+        assert_eq!(a.invert_vartime().unwrap(), a.invert().unwrap());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_le_bits_array() {
+    use rand::rngs::OsRng;
+
+    assert_eq!(Fq::zero().to_le_bits_array(), [false; 256]);
+    let mut one = [false; 256];
+    one[0] = true;
+    assert_eq!(Fq::one().to_le_bits_array(), one);
+
+    for _ in 0..10 {
+        let a = Fq::random(OsRng);
+        let bits = a.to_le_bits_array();
+        let recomposed = bits
+            .iter()
+            .rev()
+            .fold(Fq::zero(), |acc, &bit| acc.double() + Fq::from(bit));
+        assert_eq!(recomposed, a);
+        assert!(a.le_bits_iter().eq(bits.iter().copied()));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_montgomery_limbs() {
+    use rand::rngs::OsRng;
+
+    assert_eq!(Fq::one().to_montgomery_limbs(), R.0);
+    for _ in 0..10 {
+        let a = Fq::random(OsRng);
+        assert_eq!(
+            Fq::from_montgomery_limbs(a.to_montgomery_limbs()).unwrap(),
+            a
+        );
+    }
+    assert!(bool::from(Fq::from_montgomery_limbs(MODULUS.0).is_none()));
+    assert!(bool::from(
+        Fq::from_montgomery_limbs([u64::MAX; 4]).is_none()
+    ));
+
+    for _ in 0..10 {
+        let a = Fq::random(OsRng);
+        assert_eq!(Fq::from_montgomery_unchecked(a.to_montgomery_limbs()), a);
+        assert_eq!(Fq::from_raw(a.to_canonical_limbs()), a);
+    }
+    assert_eq!(Fq::from_montgomery_unchecked(R.0), Fq::one());
+    assert_eq!(Fq::one().to_canonical_limbs(), [1, 0, 0, 0]);
+    assert_eq!(
+        (-Fq::one()).to_canonical_limbs(),
+        [MODULUS.0[0] - 1, MODULUS.0[1], MODULUS.0[2], MODULUS.0[3]]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_batch_invert_in_place() {
+    use rand::rngs::OsRng;
+
+    let mut elements = [Fq::zero(); 8];
+    for e in elements.iter_mut() {
+        *e = Fq::random(OsRng);
+    }
+    let mut expected = elements;
+    for e in expected.iter_mut() {
+        *e = e.invert().unwrap();
+    }
+    let mut inverted = elements;
+    assert!(bool::from(Fq::batch_invert_in_place(&mut inverted)));
+    assert_eq!(inverted, expected);
+
+    elements[0] = Fq::zero();
+    elements[5] = Fq::zero();
+    let mut inverted = elements;
+    assert!(!bool::from(Fq::batch_invert_in_place(&mut inverted)));
+    for (a, b) in elements.iter().zip(inverted.iter()) {
+        assert_eq!(*b, a.invert().unwrap_or(Fq::zero()));
+    }
+
+    let mut empty: [Fq; 0] = [];
+    assert!(bool::from(Fq::batch_invert_in_place(&mut empty)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_multiplicative_generator() {
+    // q - 1 = 2^6 * 3 * 149 * 631 * 107361793816595537
+    //         * 174723607534414371449 * 341948486974166000522343609283189
+    let factors = [
+        "2",
+        "3",
+        "149",
+        "631",
+        "107361793816595537",
+        "174723607534414371449",
+        "341948486974166000522343609283189",
+    ];
+    let order = big_modulus() - BigUint::from(1u64);
+    let has_full_order = |g: Fq| {
+        factors.iter().all(|factor| {
+            let exp = &order / BigUint::from_str_radix(factor, 10).unwrap();
+            g.pow_vartime(exp.to_u64_digits()) != Fq::one()
+        })
+    };
+
+    let g = Fq::multiplicative_generator();
+    assert_eq!(g, Fq::from(7));
+    assert_eq!(g.pow_vartime(order.to_u64_digits()), Fq::one());
+    assert!(has_full_order(g));
+    for small in 2..7 {
+        assert!(!has_full_order(Fq::from(small)));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_root_of_unity() {
+    let t = (big_modulus() - BigUint::from(1u64)) / BigUint::from(1u64 << Fq::S);
+    assert_eq!(
+        Fq::root_of_unity(),
+        Fq::multiplicative_generator().pow_vartime(t.to_u64_digits())
+    );
+    assert_eq!(Fq::root_of_unity().pow_vartime(&[1 << Fq::S]), Fq::one());
+    assert_eq!(
+        Fq::root_of_unity().pow_vartime(&[1 << (Fq::S - 1)]),
+        -Fq::one()
+    );
+    assert_eq!(Fq::root_of_unity() * Fq::ROOT_OF_UNITY_INV, Fq::one());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_t_minus1_over2() {
+    // q - 1 = 2^S * t
+    let t = (big_modulus() - BigUint::from(1u64)) / BigUint::from(1u64 << Fq::S);
+    let tm1d2 = (t - BigUint::from(1u64)) / BigUint::from(2u64);
+    assert_eq!(tm1d2.to_u64_digits(), Fq::T_MINUS1_OVER2.to_vec());
+
+    for a in [Fq::zero(), Fq::one(), -Fq::one(), Fq::rand()].iter() {
+        assert_eq!(
+            a.pow_by_t_minus1_over2(),
+            a.pow_vartime(&Fq::T_MINUS1_OVER2)
+        );
+    }
+}
+
+#[test]
+fn test_zeta() {
+    assert_eq!(Fq::ZETA.square() * Fq::ZETA, Fq::one());
+    assert!(Fq::ZETA != Fq::one());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_zeta_endomorphism() {
+    use crate::arithmetic::CurveAffine;
+    use crate::{Fp, Secp256k1Affine};
+    use group::{Curve, GroupEncoding};
+
+    // beta, the matching cube root of unity in the base field.
+    let beta = Fp::BETA;
+    let g = Secp256k1Affine::generator();
+    let bytes = g.to_bytes();
+    let x = Fp::from_repr(bytes.as_ref()[..32].try_into().unwrap()).unwrap();
+    let y = Fp::from_repr(bytes.as_ref()[32..].try_into().unwrap()).unwrap();
+    let endo = Secp256k1Affine::from_xy(x * beta, y).unwrap();
+    assert_eq!((g * Fq::ZETA).to_affine(), endo);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_delta() {
+    assert_eq!(
+        Fq::DELTA,
+        Fq::multiplicative_generator().pow_vartime(&[1u64 << Fq::S])
+    );
+
+    // DELTA generates the subgroup of odd order t, where q - 1 = 2^S * t.
+    let t = (big_modulus() - BigUint::from(1u64)) / BigUint::from(1u64 << Fq::S);
+    assert_eq!(Fq::DELTA.pow_vartime(t.to_u64_digits()), Fq::one());
+    let factors = [
+        "3",
+        "149",
+        "631",
+        "107361793816595537",
+        "174723607534414371449",
+        "341948486974166000522343609283189",
+    ];
+    for factor in factors.iter() {
+        let exp = &t / BigUint::from_str_radix(factor, 10).unwrap();
+        assert!(Fq::DELTA.pow_vartime(exp.to_u64_digits()) != Fq::one());
+    }
+}
+
+#[test]
+fn test_sqrt() {
+    assert_eq!(Fq::zero().sqrt().unwrap(), Fq::zero());
+
+    let mut num_squares = 0;
+    for i in 1..200u64 {
+        let a = Fq::from(i);
+        let square = a.square();
+        let root = square.sqrt().unwrap();
+        assert!(root == a || root == -a);
+
+        if let Some(root) = Option::<Fq>::from(a.sqrt()) {
+            assert_eq!(root.square(), a);
+            num_squares += 1;
+        }
+    }
+    assert!(num_squares > 50 && num_squares < 150);
+
+    // A primitive 2^S-th root of unity and a generator are non-squares.
+    assert!(bool::from(Fq::root_of_unity().sqrt().is_none()));
+    assert!(bool::from(Fq::multiplicative_generator().sqrt().is_none()));
+}
+
+#[test]
+fn test_legendre() {
+    use crate::arithmetic::LegendreSymbol;
+
+    assert_eq!(Fq::zero().legendre(), LegendreSymbol::Zero);
+    assert!(bool::from(Fq::zero().is_square()));
+    assert_eq!(Fq::one().legendre(), LegendreSymbol::QuadraticResidue);
+
+    let mut num_squares = 0;
+    for i in 1..200u64 {
+        let a = Fq::from(i);
+        assert_eq!(a.square().legendre(), LegendreSymbol::QuadraticResidue);
+        assert!(bool::from(a.square().is_square()));
+
+        let is_square = bool::from(a.is_square());
+        assert_eq!(
+            a.legendre(),
+            if is_square {
+                LegendreSymbol::QuadraticResidue
+            } else {
+                LegendreSymbol::QuadraticNonResidue
+            }
+        );
+        num_squares += is_square as usize;
+    }
+    assert!(num_squares > 50 && num_squares < 150);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_batch_invert() {
+    use rand::rngs::OsRng;
+    use std::vec::Vec;
+
+    for &n in [0, 1, 2, 100].iter() {
+        let mut elements: Vec<Fq> = (0..n).map(|_| Fq::random(OsRng)).collect();
+        let expected: Vec<Fq> = elements.iter().map(|e| e.invert().unwrap()).collect();
+        assert_eq!(Fq::batch_inverted(&elements), expected);
+        assert!(bool::from(Fq::batch_invert(&mut elements)));
+        assert_eq!(elements, expected);
+    }
+
+    let elements = [Fq::from(2), Fq::zero(), Fq::from(3), Fq::zero()];
+    let mut inverted = elements;
+    assert!(!bool::from(Fq::batch_invert(&mut inverted)));
+    for (a, b) in elements.iter().zip(inverted.iter()) {
+        assert_eq!(*b, a.invert().unwrap_or(Fq::zero()));
+    }
+}
+
+#[test]
+fn test_from_bytes_wide() {
+    let mut bytes = [0u8; 64];
+    assert_eq!(Fq::from_bytes_wide(&bytes), Fq::zero());
+
+    // 2^256 + 5
+    bytes[0] = 5;
+    bytes[32] = 1;
+    assert_eq!(Fq::from_bytes_wide(&bytes), Fq::from_raw(R.0) + Fq::from(5));
+
+    // -1 + q reduces to -1.
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&(-Fq::one()).to_repr());
+    assert_eq!(Fq::from_bytes_wide(&bytes), -Fq::one());
+
+    assert_eq!(
+        Fq::from_u128(u128::MAX),
+        Fq::from_raw([u64::MAX, u64::MAX, 0, 0])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[cfg(feature = "std")]
+#[test]
+fn test_serde() {
+    use rand::rngs::OsRng;
+
+    for a in [Fq::zero(), Fq::one(), -Fq::one(), Fq::random(OsRng)].iter() {
+        let json = serde_json::to_string(a).unwrap();
+        assert_eq!(json, format!("\"{:?}\"", a));
+        assert_eq!(serde_json::from_str::<Fq>(&json).unwrap(), *a);
+
+        let bytes = bincode::serialize(a).unwrap();
+        assert_eq!(bytes, a.to_repr());
+        assert_eq!(bincode::deserialize::<Fq>(&bytes).unwrap(), *a);
+    }
+
+    let upper = format!("\"{:?}\"", -Fq::one())
+        .to_uppercase()
+        .replace("0X", "0x");
+    assert_eq!(serde_json::from_str::<Fq>(&upper).unwrap(), -Fq::one());
+
+    // The modulus is not canonical.
+    let mut modulus = [0u8; 32];
+    for (bytes, limb) in modulus.chunks_mut(8).zip(MODULUS.0.iter()) {
+        bytes.copy_from_slice(&limb.to_le_bytes());
+    }
+    assert!(bincode::deserialize::<Fq>(&modulus).is_err());
+    let hex: std::string::String = modulus.iter().rev().map(|b| format!("{:02x}", b)).collect();
+    let json = format!("\"0x{}\"", hex);
+    assert!(serde_json::from_str::<Fq>(&json).is_err());
+
+    for bad in [
+        "\"1\"",
+        "\"0x01\"",
+        "\"0000000000000000000000000000000000000000000000000000000000000001\"",
+        "\"0x000000000000000000000000000000000000000000000000000000000000000g\"",
+        "\"0x+00000000000000000000000000000000000000000000000000000000000001\"",
+    ]
+    .iter()
+    {
+        assert!(serde_json::from_str::<Fq>(bad).is_err());
+    }
+    assert!(bincode::deserialize::<Fq>(&[0u8; 31]).is_err());
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg(feature = "std")]
+#[test]
+fn test_zeroize() {
+    use rand::rngs::OsRng;
+    use zeroize::Zeroize;
+
+    let mut a = Fq::random(OsRng);
+    a.zeroize();
+    assert_eq!(a, Fq::zero());
+
+    let b = Fq::random(OsRng);
+    let mut secret = SecretFq::new(b);
+    assert_eq!(*secret.expose_secret(), b);
+    assert_eq!(format!("{:?}", secret), "SecretFq(..)");
+    secret.zeroize();
+    assert_eq!(*secret.expose_secret(), Fq::zero());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hash() {
+    use core::hash::{Hash, Hasher};
+    use rand::rngs::OsRng;
+    use std::collections::{hash_map::DefaultHasher, HashSet};
+
+    fn hash(a: &Fq) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        a.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let a = Fq::random(OsRng);
+    assert_eq!(hash(&a), hash(&(a + Fq::one() - Fq::one())));
+    assert_eq!(hash(&Fq::from(5)), hash(&Fq::from_raw([5, 0, 0, 0])));
+
+    let set: HashSet<Fq> = [a, Fq::one(), a.double() - a, Fq::one() * Fq::one()]
+        .iter()
+        .cloned()
+        .collect();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&a));
+    assert!(!set.contains(&Fq::zero()));
+}
+
+#[test]
+fn test_sum_product() {
+    let elements = [Fq::from(2), Fq::from(3), Fq::from(5), -Fq::one()];
+
+    assert_eq!(elements.iter().sum::<Fq>(), Fq::from(9));
+    assert_eq!(elements.iter().cloned().sum::<Fq>(), Fq::from(9));
+    assert_eq!(elements.iter().product::<Fq>(), -Fq::from(30));
+    assert_eq!(elements.iter().cloned().product::<Fq>(), -Fq::from(30));
+
+    assert_eq!(elements[..0].iter().sum::<Fq>(), Fq::zero());
+    assert_eq!(elements[..0].iter().product::<Fq>(), Fq::one());
+}
+
+#[test]
+fn test_from_integers() {
+    assert_eq!(Fq::from(7u8), Fq::from(7u64));
+    assert_eq!(Fq::from(7u16), Fq::from(7u64));
+    assert_eq!(Fq::from(7u32), Fq::from(7u64));
+    assert_eq!(Fq::from(7usize), Fq::from(7u64));
+    assert_eq!(Fq::from(7), Fq::from(7u64));
+    assert_eq!(
+        Fq::from(u128::MAX),
+        Fq::from_raw([u64::MAX, u64::MAX, 0, 0])
+    );
+    assert_eq!(Fq::from(u128::MAX), Fq::from_u128(u128::MAX));
+
+    assert_eq!(Fq::from(-1), -Fq::one());
+    assert_eq!(Fq::from(-7i8), -Fq::from(7u64));
+    assert_eq!(Fq::from(-7i16), -Fq::from(7u64));
+    assert_eq!(Fq::from(-7i64), -Fq::from(7u64));
+    assert_eq!(Fq::from(i64::MIN), -Fq::from(1u64 << 63));
+    assert_eq!(Fq::from(i64::MAX), Fq::from(i64::MAX as u64));
+    assert_eq!(Fq::from(0i64), Fq::zero());
+    assert_eq!(Fq::from(i128::MIN), -Fq::from(1u128 << 127));
+    assert_eq!(Fq::from(-3i128), -Fq::from(3u64));
+}
+
+#[test]
+fn test_from_hex() {
+    assert_eq!(Fq::from_hex("0x0"), Fq::zero());
+    assert_eq!(Fq::from_hex("1"), Fq::one());
+    assert_eq!(Fq::from_hex("0xDeadBeef"), Fq::from(0xdeadbeefu64));
+    assert_eq!(fq!("0x10000000000000000"), Fq::from_raw([0, 1, 0, 0]));
+    assert_eq!(
+        fq!("0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140"),
+        -Fq::one()
+    );
+    assert_eq!(Fq::from_hex(&format!("{:?}", Fq::ZETA)), Fq::ZETA);
+}
+
+#[test]
+#[should_panic]
+fn test_from_hex_modulus() {
+    Fq::from_hex("0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+}
+
+#[test]
+#[should_panic]
+fn test_from_hex_invalid_digit() {
+    Fq::from_hex("0x12g4");
+}
+
+#[test]
+#[should_panic]
+fn test_from_hex_empty() {
+    Fq::from_hex("0x");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_display_hex() {
+    let minus_one = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140";
+    assert_eq!(format!("{}", -Fq::one()), format!("0x{}", minus_one));
+    assert_eq!(format!("{:x}", -Fq::one()), minus_one);
+    assert_eq!(format!("{:#x}", -Fq::one()), format!("0x{}", minus_one));
+    assert_eq!(format!("{:X}", -Fq::one()), minus_one.to_uppercase());
+    assert_eq!(
+        format!("{:#X}", -Fq::one()),
+        format!("0x{}", minus_one.to_uppercase())
+    );
+
+    let five = format!("{:064x}", 5);
+    assert_eq!(format!("{:x}", Fq::from(5)), five);
+    assert_eq!(format!("{}", Fq::from(5)), format!("{:?}", Fq::from(5)));
+}
+
+#[test]
+fn test_try_from_slice() {
+    use crate::fields::FieldBytesError;
+    use core::convert::TryFrom;
+
+    let a = -Fq::from(7);
+    let repr = a.to_repr();
+    assert_eq!(Fq::try_from(&repr[..]), Ok(a));
+    let mut be = repr;
+    be.reverse();
+    assert_eq!(Fq::from_bytes_be(&be), Ok(a));
+
+    let invalid_length = FieldBytesError::InvalidLength {
+        expected: 32,
+        actual: 31,
+    };
+    assert_eq!(Fq::try_from(&repr[1..]), Err(invalid_length));
+    assert_eq!(Fq::from_bytes_be(&be[1..]), Err(invalid_length));
+    assert!(Fq::try_from(&[0u8; 33][..]).is_err());
+
+    assert_eq!(
+        Fq::try_from(&[0xff; 32][..]),
+        Err(FieldBytesError::NonCanonical)
+    );
+    assert_eq!(
+        Fq::from_bytes_be(&[0xff; 32]),
+        Err(FieldBytesError::NonCanonical)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parity() {
+    use rand::rngs::OsRng;
+
+    assert!(bool::from(Fq::zero().is_even()));
+    assert!(bool::from(Fq::one().is_odd()));
+    // The modulus is odd, so -1 is even.
+    assert!(bool::from((-Fq::one()).is_even()));
+    for _ in 0..100 {
+        let a = Fq::random(OsRng);
+        let odd = a.to_repr()[0] & 1 == 1;
+        assert_eq!(bool::from(a.is_odd()), odd);
+        assert_eq!(bool::from(a.is_even()), !odd);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_double() {
+    use rand::rngs::OsRng;
+
+    assert_eq!(Fq::zero().double(), Fq::zero());
+    assert_eq!(Fq::one().double(), Fq::from(2));
+    assert_eq!((-Fq::one()).double(), -Fq::from(2));
+    // 1/2 = (q + 1) / 2 doubles to q + 1, which must be reduced to one.
+    let half = Fq::from(2).invert().unwrap();
+    assert_eq!(half.double(), Fq::one());
+    assert_eq!((half + Fq::one()).double(), Fq::from(3));
+    for _ in 0..100 {
+        let a = Fq::random(OsRng);
+        assert_eq!(a.double(), a + a);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_pow() {
+    use rand::{rngs::OsRng, RngCore};
+
+    let a = Fq::random(OsRng);
+    assert_eq!(a.pow(&[0, 0, 0, 0]), Fq::one());
+    assert_eq!(a.pow(&[1, 0, 0, 0]), a);
+    assert_eq!(a.pow(&[0x11, 0, 0, 0]), a.pow_vartime(&[0x11]));
+    assert_eq!(Fq::zero().pow(&[5, 0, 0, 0]), Fq::zero());
+    assert_eq!(a.pow(&[u64::MAX; 4]), a.pow_vartime(&[u64::MAX; 4]));
+    for _ in 0..10 {
+        let exp = [
+            OsRng.next_u64(),
+            OsRng.next_u64(),
+            OsRng.next_u64(),
+            OsRng.next_u64(),
+        ];
+        assert_eq!(a.pow(&exp), a.pow_vartime(&exp));
+        assert_eq!(FieldExt::pow(&a, &exp), a.pow(&exp));
+    }
+}
+
+#[test]
+fn test_from_u512() {
+    // 2^256 + 5, reduced at compile time.
+    const WIDE: Fq = Fq::from_u512([5, 0, 0, 0, 1, 0, 0, 0]);
+    assert_eq!(WIDE, Fq::from_raw(R.0) + Fq::from(5));
+    assert_eq!(Fq::from_u512([u64::MAX; 8]), Fq::from_raw(R2.0) - Fq::one());
+
+    let mut bytes = [0u8; 64];
+    bytes[0] = 5;
+    bytes[32] = 1;
+    assert_eq!(Fq::from_bytes_wide(&bytes), WIDE);
+    assert_eq!(
+        Fq::from_bytes_wide(&[0xff; 64]),
+        Fq::from_u512([u64::MAX; 8])
+    );
+}
+
+#[test]
+fn test_bytes_be() {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(Fq::one().to_bytes_be(), one);
+    assert_eq!(Fq::from_repr_be(&one).unwrap(), Fq::one());
+
+    let a = Fq::from_raw([
+        0x0123456789abcdef,
+        0xfedcba9876543210,
+        0x0011223344556677,
+        0x8899aabbccddeeff,
+    ]);
+    let be = a.to_bytes_be();
+    assert_eq!(be[..8], 0x8899aabbccddeeffu64.to_be_bytes());
+    assert_eq!(be[24..], 0x0123456789abcdefu64.to_be_bytes());
+    assert_eq!(Fq::from_repr_be(&be).unwrap(), a);
+    assert_eq!(Fq::from_bytes_be(&be), Ok(a));
+
+    let mut modulus = MODULUS.0;
+    modulus.reverse();
+    let mut be = [0u8; 32];
+    for (bytes, limb) in be.chunks_mut(8).zip(modulus.iter()) {
+        bytes.copy_from_slice(&limb.to_be_bytes());
+    }
+    assert!(bool::from(Fq::from_repr_be(&be).is_none()));
+}
+
+#[test]
+fn test_u128() {
+    let v = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210u128;
+    const V: Fq = Fq::from_u128(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+    assert_eq!(V, Fq::from(v));
+    assert_eq!(V.get_lower_128(), v);
+    assert_eq!(Fq::from_u128(u128::MAX).get_lower_128(), u128::MAX);
+    assert_eq!(V.double().get_lower_128(), v << 1);
+}
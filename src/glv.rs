@@ -0,0 +1,234 @@
+//! This module contains the GLV decomposition of scalars and point
+//! multiplication accelerated with the secp256k1 endomorphism, following
+//! libsecp256k1.
+//!
+//! A scalar $k$ is split into $k_1 + k_2 \lambda$ with $|k_1|, |k_2| < 2^{128}$,
+//! where $\lambda$ = `Fq::ZETA`, so that $k P = k_1 P + k_2 (\lambda P)$ and
+//! $\lambda P$ costs a single field multiplication. Interleaving the wNAF
+//! forms of the halves halves the number of doublings.
+
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+use crate::arithmetic::{adc, mac};
+use crate::wnaf::{wnaf_digits, wnaf_sum_vartime, Wnaf, WNAF_NUM_DIGITS};
+
+use super::{Fq, Secp256k1};
+
+/// The wNAF table size used by [`Secp256k1::double_mul_vartime`], for a
+/// window width of 5.
+const GLV_WNAF_TABLE_SIZE: usize = 8;
+
+/// $\mathrm{round}(2^{384} b_2 / q)$.
+const G1: [u64; 4] = [
+    0xe893209a45dbb031,
+    0x3daa8a1471e8ca7f,
+    0xe86c90e49284eb15,
+    0x3086d221a7d46bcd,
+];
+
+/// $\mathrm{round}(2^{384} (-b_1) / q)$.
+const G2: [u64; 4] = [
+    0x1571b4ae8ac47f71,
+    0x221208ac9df506c6,
+    0x6f547fa90abfe4c4,
+    0xe4437ed6010e8828,
+];
+
+/// $-b_1$, where $(a_1, b_1)$ and $(a_2, b_2)$ are the short basis of the
+/// lattice of pairs $(x, y)$ with $x + y \lambda = 0$.
+const MINUS_B1: Fq = Fq::from_raw([0x6f547fa90abfe4c3, 0xe4437ed6010e8828, 0, 0]);
+
+/// $-b_2$.
+const MINUS_B2: Fq = Fq::from_raw([
+    0xd765cda83db1562c,
+    0x8a280ac50774346d,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+]);
+
+/// $-\lambda$.
+const MINUS_LAMBDA: Fq = Fq::from_raw([
+    0xe0cfc810b51283cf,
+    0xa880b9fc8ec739c2,
+    0x5ad9e3fd77ed9ba4,
+    0xac9c52b33fa3cf1f,
+]);
+
+/// Returns $\mathrm{round}(k g / 2^{384})$, which is less than $2^{128}$ for
+/// the constants above.
+fn mul_shift_384(k: &[u64; 4], g: &[u64; 4]) -> Fq {
+    let mut product = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0;
+        for j in 0..4 {
+            let (limb, next) = mac(product[i + j], k[i], g[j], carry);
+            product[i + j] = limb;
+            carry = next;
+        }
+        product[i + 4] = carry;
+    }
+
+    // Round by adding the highest bit shifted out.
+    let (lo, carry) = adc(product[6], 0, product[5] >> 63);
+    let (hi, _) = adc(product[7], 0, carry);
+    Fq::from_raw([lo, hi, 0, 0])
+}
+
+/// Returns the magnitude of `r`, which must be less than $2^{128}$ in
+/// absolute value, and whether it is negative.
+fn to_signed_128(r: &Fq) -> (u128, bool) {
+    let limbs = r.to_canonical_limbs();
+    let is_neg = !(limbs[2] | limbs[3]).ct_eq(&0);
+    let r = Fq::conditional_select(r, &-r, is_neg);
+    (r.get_lower_128(), bool::from(is_neg))
+}
+
+impl Secp256k1 {
+    /// Splits `k` into $(k_1, k_2)$ with $k = k_1 + k_2 \lambda$ for
+    /// $\lambda$ = `Fq::ZETA`, returning $(|k_1|, k_1 < 0, |k_2|, k_2 < 0)$.
+    ///
+    /// Both halves are less than $2^{128}$ in absolute value, so that
+    /// $k P$ = `P.mul_short(k1, k1_neg) + P.endo().mul_short(k2, k2_neg)`. The
+    /// arithmetic is constant time in `k`.
+    pub fn decompose_scalar(k: &Fq) -> (u128, bool, u128, bool) {
+        let limbs = k.to_canonical_limbs();
+        let c1 = mul_shift_384(&limbs, &G1);
+        let c2 = mul_shift_384(&limbs, &G2);
+
+        let k2 = c1 * MINUS_B1 + c2 * MINUS_B2;
+        let k1 = k + k2 * MINUS_LAMBDA;
+
+        let (k1, k1_neg) = to_signed_128(&k1);
+        let (k2, k2_neg) = to_signed_128(&k2);
+        (k1, k1_neg, k2, k2_neg)
+    }
+
+    /// Computes $a P + b Q$, splitting both scalars with
+    /// [`Secp256k1::decompose_scalar`] and running one interleaved wNAF over
+    /// $P$, $\lambda P$, $Q$ and $\lambda Q$. This takes about 128 doublings
+    /// instead of the 256 of a plain double-and-add.
+    ///
+    /// The four tables live on the stack. This is not constant time; it is
+    /// meant for signature verification, where every input is public.
+    pub fn double_mul_vartime(a: &Fq, p: &Secp256k1, b: &Fq, q: &Secp256k1) -> Secp256k1 {
+        let p = Wnaf::<GLV_WNAF_TABLE_SIZE>::new(p);
+        let q = Wnaf::<GLV_WNAF_TABLE_SIZE>::new(q);
+        glv_double_mul_vartime(a, (&p, &p.endo()), b, (&q, &q.endo()))
+    }
+}
+
+/// Returns the width-`width` NAF of $\pm k$, negated if `negate` is set.
+fn short_digits(k: u128, negate: bool, width: usize) -> [i16; WNAF_NUM_DIGITS] {
+    let mut digits = wnaf_digits([k as u64, (k >> 64) as u64, 0, 0], width);
+    if negate {
+        for digit in digits.iter_mut() {
+            *digit = -*digit;
+        }
+    }
+    digits
+}
+
+/// Computes $a P + b Q$ from the wNAF tables of $P$, $\lambda P$, $Q$ and
+/// $\lambda Q$, as [`Secp256k1::double_mul_vartime`] does. This is not
+/// constant time.
+pub(crate) fn glv_double_mul_vartime<const N1: usize, const N2: usize>(
+    a: &Fq,
+    (p, p_endo): (&Wnaf<N1>, &Wnaf<N1>),
+    b: &Fq,
+    (q, q_endo): (&Wnaf<N2>, &Wnaf<N2>),
+) -> Secp256k1 {
+    let (a1, a1_neg, a2, a2_neg) = Secp256k1::decompose_scalar(a);
+    let (b1, b1_neg, b2, b2_neg) = Secp256k1::decompose_scalar(b);
+    wnaf_sum_vartime(&[
+        (
+            p.odd_multiples(),
+            short_digits(a1, a1_neg, Wnaf::<N1>::WIDTH),
+        ),
+        (
+            p_endo.odd_multiples(),
+            short_digits(a2, a2_neg, Wnaf::<N1>::WIDTH),
+        ),
+        (
+            q.odd_multiples(),
+            short_digits(b1, b1_neg, Wnaf::<N2>::WIDTH),
+        ),
+        (
+            q_endo.odd_multiples(),
+            short_digits(b2, b2_neg, Wnaf::<N2>::WIDTH),
+        ),
+    ])
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_decompose_scalar() {
+    use crate::arithmetic::FieldExt;
+    use ff::Field;
+    use rand::rngs::OsRng;
+
+    let half = Fq::from_raw([
+        0xdfe92f46681b20a0,
+        0x5d576e7357a4501d,
+        0xffffffffffffffff,
+        0x7fffffffffffffff,
+    ]);
+    let mut scalars = [
+        Fq::zero(),
+        Fq::one(),
+        -Fq::one(),
+        Fq::ZETA,
+        -Fq::ZETA,
+        half,
+        half + Fq::one(),
+        Fq::zero(),
+    ];
+    for _ in 0..100 {
+        scalars[7] = Fq::random(OsRng);
+        for k in scalars.iter() {
+            let (k1, k1_neg, k2, k2_neg) = Secp256k1::decompose_scalar(k);
+            let k1 = Fq::from_u128(k1);
+            let k2 = Fq::from_u128(k2);
+            let k1 = if k1_neg { -k1 } else { k1 };
+            let k2 = if k2_neg { -k2 } else { k2 };
+            assert_eq!(k1 + k2 * Fq::ZETA, *k);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_double_mul_vartime() {
+    use ff::Field;
+    use group::Group;
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    let q = Secp256k1::random(OsRng);
+    let scalars = [
+        Fq::zero(),
+        Fq::one(),
+        -Fq::one(),
+        Fq::random(OsRng),
+        Fq::random(OsRng),
+    ];
+    for a in scalars.iter() {
+        for b in scalars.iter() {
+            assert_eq!(Secp256k1::double_mul_vartime(a, &p, b, &q), p * a + q * b);
+        }
+    }
+    let a = Fq::random(OsRng);
+    assert_eq!(
+        Secp256k1::double_mul_vartime(&a, &p, &-a, &p),
+        Secp256k1::identity()
+    );
+    assert_eq!(
+        Secp256k1::double_mul_vartime(&a, &p, &a, &Secp256k1::identity()),
+        p * a
+    );
+
+    let (k1, k1_neg, k2, k2_neg) = Secp256k1::decompose_scalar(&a);
+    assert_eq!(
+        p.mul_short(k1, k1_neg) + p.endo().mul_short(k2, k2_neg),
+        p * a
+    );
+}
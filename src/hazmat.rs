@@ -0,0 +1,164 @@
+//! This module contains low-level ECDSA signing primitives for protocol
+//! research, such as adaptor signatures and custom nonce schemes.
+//!
+//! # Security
+//!
+//! These functions perform none of the safeguards a signing API needs. Reusing
+//! or biasing a nonce passed to [`sign_prehashed_with_nonce`] leaks the private
+//! key (see [`crate::ecdsa::recover_reused_nonce`]), and a signature checked by
+//! [`verify_prehashed_with_generator`] against anything but the standard
+//! generator is not an ECDSA signature. Only use them if you know exactly why
+//! you need them.
+
+use ff::{Field, PrimeField};
+use group::{Curve, GroupEncoding};
+
+use crate::ecdsa::RecoveryId;
+
+use super::{Fq, Secp256k1, Secp256k1Affine};
+
+/// Reduces the big-endian message digest `digest` to the challenge scalar
+/// $z$ modulo $q$, as done for 256-bit hashes by ECDSA and by the BIP-340
+/// challenge.
+pub fn challenge_from_digest(digest: &[u8; 32]) -> Fq {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(digest);
+    wide[..32].reverse();
+    Fq::from_bytes_wide(&wide)
+}
+
+/// Returns the signature value $r$, the x-coordinate of `point` reduced
+/// modulo $q$, together with the recovery id of `point`.
+pub fn r_from_point(point: &Secp256k1Affine) -> (Fq, RecoveryId) {
+    let bytes = point.to_bytes();
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&bytes.as_ref()[..32]);
+
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(&x);
+    let r = Fq::from_bytes_wide(&wide);
+
+    let is_y_odd = bytes.as_ref()[32] & 1 == 1;
+    let is_x_reduced = bool::from(Fq::from_repr(x).is_none());
+    (r, RecoveryId::new(is_y_odd, is_x_reduced))
+}
+
+/// Signs the challenge `z` with the private key `secret_key` and the
+/// caller-provided nonce `nonce`, returning $(r, s)$ with
+/// $s = k^{-1} (z + r d)$ and the recovery id of $R = k \cdot G$.
+///
+/// `s` is not normalized to low-S; see [`crate::ecdsa::normalize_s`]. Returns
+/// `None` if $r$ or $s$ is zero, in which case another nonce must be used.
+/// This is constant time in `secret_key` and `nonce` when it succeeds.
+pub fn sign_prehashed_with_nonce(
+    secret_key: &Fq,
+    nonce: &Fq,
+    z: &Fq,
+) -> Option<(Fq, Fq, RecoveryId)> {
+    let (r, recovery_id) = r_from_point(&(Secp256k1::generator() * nonce).to_affine());
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut nonce_inv = Option::<Fq>::from(nonce.invert())?;
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut rd = r * secret_key;
+    let s = nonce_inv * (z + rd);
+
+    // Either value would reveal the secret key together with the signature.
+    #[cfg(feature = "zeroize")]
+    {
+        zeroize::Zeroize::zeroize(&mut nonce_inv);
+        zeroize::Zeroize::zeroize(&mut rd);
+    }
+
+    if bool::from(r.is_zero() | s.is_zero()) {
+        return None;
+    }
+    Some((r, s, recovery_id))
+}
+
+/// Checks the signature $(r, s)$ of the challenge `z` by `public_key`, with
+/// `generator` in place of the standard generator: that is, that the
+/// x-coordinate of $(z / s) \cdot \mathit{generator} + (r / s) \cdot
+/// \mathit{public\_key}$ is $r$ modulo $q$.
+///
+/// The two multiplications share their doublings through
+/// [`Secp256k1::double_mul_vartime`], which splits both scalars with the GLV
+/// endomorphism. Both low-S and high-S signatures are accepted. This is not
+/// constant time: every input is public.
+pub fn verify_prehashed_with_generator(
+    generator: &Secp256k1,
+    public_key: &Secp256k1,
+    z: &Fq,
+    r: &Fq,
+    s: &Fq,
+) -> bool {
+    if bool::from(r.is_zero()) {
+        return false;
+    }
+    let s_inv = match Option::<Fq>::from(s.invert()) {
+        Some(s_inv) => s_inv,
+        None => return false,
+    };
+
+    let point = Secp256k1::double_mul_vartime(&(z * s_inv), generator, &(r * s_inv), public_key);
+    is_r_of_point(&point.to_affine(), r)
+}
+
+/// Returns whether `point`, the $R$ recomputed by a verifier, is not the
+/// identity and gives the signature value `r`.
+pub(crate) fn is_r_of_point(point: &Secp256k1Affine, r: &Fq) -> bool {
+    !bool::from(point.is_identity()) && r_from_point(point).0 == *r
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_sign_verify() {
+    use group::Group;
+    use rand::rngs::OsRng;
+
+    let secret_key = Fq::random(OsRng);
+    let nonce = Fq::random(OsRng);
+    let z = challenge_from_digest(&[0x5a; 32]);
+    let generator = Secp256k1::generator();
+    let public_key = generator * secret_key;
+    let verify = |base: Secp256k1, key: Secp256k1, z: Fq, r: Fq, s: Fq| {
+        verify_prehashed_with_generator(&base, &key, &z, &r, &s)
+    };
+
+    let (r, s, recovery_id) = sign_prehashed_with_nonce(&secret_key, &nonce, &z).unwrap();
+    assert!(verify(generator, public_key, z, r, s));
+    assert!(verify(generator, public_key, z, r, -s));
+    assert!(!verify(generator, public_key, z + Fq::one(), r, s));
+    assert!(!verify(generator, public_key, z, r, Fq::zero()));
+    assert!(!verify(generator, public_key, z, Fq::zero(), s));
+
+    assert_eq!(
+        (r, recovery_id),
+        r_from_point(&(generator * nonce).to_affine())
+    );
+    assert_eq!(s, nonce.invert().unwrap() * (z + r * secret_key));
+    assert_eq!(
+        sign_prehashed_with_nonce(&secret_key, &Fq::zero(), &z),
+        None
+    );
+
+    // A signature relative to another generator.
+    let other = Secp256k1::random(OsRng);
+    let public_key = other * secret_key;
+    let (r, _) = r_from_point(&(other * nonce).to_affine());
+    let s = nonce.invert().unwrap() * (z + r * secret_key);
+    assert!(verify(other, public_key, z, r, s));
+    assert!(!verify(generator, public_key, z, r, s));
+}
+
+#[test]
+fn test_challenge_from_digest() {
+    let mut digest = [0u8; 32];
+    digest[31] = 7;
+    assert_eq!(challenge_from_digest(&digest), Fq::from(7));
+
+    // q + 1 reduces to one.
+    let mut digest = (-Fq::one()).to_repr();
+    digest.reverse();
+    digest[31] += 2;
+    assert_eq!(challenge_from_digest(&digest), Fq::one());
+}
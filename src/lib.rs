@@ -1,4 +1,14 @@
 //! Implementation of the Pallas / Vesta curve cycle.
+//!
+//! # Timing
+//!
+//! Field and point arithmetic, encoding and decoding run in time independent
+//! of the values they operate on, unless the function name ends in `_vartime`
+//! or its documentation says it is not constant time. Each variable-time
+//! function that has a constant-time counterpart names it, e.g.
+//! [`Fq::invert_vartime`], [`Secp256k1::add_vartime`],
+//! [`Secp256k1::mul_vartime`] and [`Secp256k1Affine::from_bytes_vartime`].
+//! The variable-time functions must only be used on public values.
 
 #![no_std]
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -17,10 +27,38 @@ extern crate std;
 mod macros;
 mod curves;
 mod fields;
+mod glv;
+#[cfg(feature = "std")]
+mod msm;
+#[cfg(feature = "std")]
+mod pedersen;
+#[cfg(feature = "std")]
+mod table;
+#[cfg(feature = "std")]
+mod trace;
+mod wnaf;
 
 pub mod arithmetic;
+#[cfg(feature = "differential")]
+pub mod differential;
+pub mod ecdsa;
+pub mod hazmat;
+#[cfg(feature = "std")]
+pub mod poly;
+#[cfg(feature = "std")]
+pub mod serde;
+pub mod taproot;
 
 pub use curves::*;
 pub use fields::*;
+#[cfg(feature = "std")]
+pub use msm::*;
+#[cfg(feature = "std")]
+pub use pedersen::*;
+#[cfg(feature = "std")]
+pub use table::*;
+#[cfg(feature = "std")]
+pub use trace::*;
+pub use wnaf::*;
 
 pub extern crate group;
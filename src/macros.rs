@@ -151,3 +151,1123 @@ macro_rules! impl_binops_multiplicative {
         }
     };
 }
+
+/// Implements the Montgomery arithmetic and the common trait impls shared by
+/// the prime fields of this crate. The field type must be a newtype over
+/// `[u64; 4]` held in Montgomery form, and the remaining arguments name its
+/// modulus, `INV = -(m^{-1} mod 2^64) mod 2^64` and `R`, `R^2`, `R^3` modulo
+/// the modulus.
+///
+/// An optional last argument names the function from `crate::arithmetic`
+/// used for the Montgomery reduction, which defaults to
+/// `montgomery_reduce_wide`.
+macro_rules! field_impl {
+    ($field:ident, $modulus:ident, $inv:ident, $r:ident, $r2:ident, $r3:ident) => {
+        field_impl!($field, $modulus, $inv, $r, $r2, $r3, montgomery_reduce_wide);
+    };
+    ($field:ident, $modulus:ident, $inv:ident, $r:ident, $r2:ident, $r3:ident, $reduce:ident) => {
+        // `reduce_320` folds the top limb back in twice, which is enough only
+        // when 2^256 mod m fits in 192 bits.
+        static_assertions::const_assert!($r.0[3] == 0);
+
+        impl fmt::Debug for $field {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let tmp = self.to_repr();
+                write!(f, "0x")?;
+                for &b in tmp.iter().rev() {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::Display for $field {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{:#x}", self)
+            }
+        }
+
+        impl fmt::LowerHex for $field {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                if f.alternate() {
+                    write!(f, "0x")?;
+                }
+                for &b in self.to_repr().iter().rev() {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::UpperHex for $field {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                if f.alternate() {
+                    write!(f, "0x")?;
+                }
+                for &b in self.to_repr().iter().rev() {
+                    write!(f, "{:02X}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl From<bool> for $field {
+            fn from(bit: bool) -> $field {
+                if bit {
+                    $field::one()
+                } else {
+                    $field::zero()
+                }
+            }
+        }
+
+        impl From<u64> for $field {
+            fn from(val: u64) -> $field {
+                $field([val, 0, 0, 0]) * $r2
+            }
+        }
+
+        impl From<u8> for $field {
+            fn from(val: u8) -> $field {
+                $field::from(u64::from(val))
+            }
+        }
+
+        impl From<u16> for $field {
+            fn from(val: u16) -> $field {
+                $field::from(u64::from(val))
+            }
+        }
+
+        impl From<u32> for $field {
+            fn from(val: u32) -> $field {
+                $field::from(u64::from(val))
+            }
+        }
+
+        impl From<usize> for $field {
+            fn from(val: usize) -> $field {
+                $field::from(val as u64)
+            }
+        }
+
+        impl From<u128> for $field {
+            fn from(val: u128) -> $field {
+                $field([val as u64, (val >> 64) as u64, 0, 0]) * $r2
+            }
+        }
+
+        impl From<i8> for $field {
+            fn from(val: i8) -> $field {
+                $field::from(i64::from(val))
+            }
+        }
+
+        impl From<i16> for $field {
+            fn from(val: i16) -> $field {
+                $field::from(i64::from(val))
+            }
+        }
+
+        impl From<i32> for $field {
+            fn from(val: i32) -> $field {
+                $field::from(i64::from(val))
+            }
+        }
+
+        impl From<i64> for $field {
+            /// Maps a negative `val` to the modulus minus `|val|`.
+            fn from(val: i64) -> $field {
+                let abs = $field::from(val.unsigned_abs());
+                $field::conditional_select(&abs, &-abs, Choice::from((val < 0) as u8))
+            }
+        }
+
+        impl From<i128> for $field {
+            /// Maps a negative `val` to the modulus minus `|val|`.
+            fn from(val: i128) -> $field {
+                let abs = $field::from(val.unsigned_abs());
+                $field::conditional_select(&abs, &-abs, Choice::from((val < 0) as u8))
+            }
+        }
+
+        impl ConstantTimeEq for $field {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.0[0].ct_eq(&other.0[0])
+                    & self.0[1].ct_eq(&other.0[1])
+                    & self.0[2].ct_eq(&other.0[2])
+                    & self.0[3].ct_eq(&other.0[3])
+            }
+        }
+
+        impl PartialEq for $field {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.ct_eq(other).unwrap_u8() == 1
+            }
+        }
+
+        impl core::iter::Sum for $field {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> core::iter::Sum<&'a $field> for $field {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |acc, x| acc + x)
+            }
+        }
+
+        impl core::iter::Product for $field {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), |acc, x| acc * x)
+            }
+        }
+
+        impl<'a> core::iter::Product<&'a $field> for $field {
+            fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), |acc, x| acc * x)
+            }
+        }
+
+        impl core::hash::Hash for $field {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                // Hash the canonical representation, so that equal elements
+                // hash equally independently of the internal one.
+                self.to_repr().hash(state);
+            }
+        }
+
+        impl core::cmp::Ord for $field {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                let left = self.to_repr();
+                let right = other.to_repr();
+                left.iter()
+                    .zip(right.iter())
+                    .rev()
+                    .find_map(|(left_byte, right_byte)| match left_byte.cmp(right_byte) {
+                        core::cmp::Ordering::Equal => None,
+                        res => Some(res),
+                    })
+                    .unwrap_or(core::cmp::Ordering::Equal)
+            }
+        }
+
+        impl core::cmp::PartialOrd for $field {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl ConditionallySelectable for $field {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $field([
+                    u64::conditional_select(&a.0[0], &b.0[0], choice),
+                    u64::conditional_select(&a.0[1], &b.0[1], choice),
+                    u64::conditional_select(&a.0[2], &b.0[2], choice),
+                    u64::conditional_select(&a.0[3], &b.0[3], choice),
+                ])
+            }
+        }
+
+        impl<'a> Neg for &'a $field {
+            type Output = $field;
+
+            #[inline]
+            fn neg(self) -> $field {
+                self.neg()
+            }
+        }
+
+        impl Neg for $field {
+            type Output = $field;
+
+            #[inline]
+            fn neg(self) -> $field {
+                -&self
+            }
+        }
+
+        impl<'a, 'b> Sub<&'b $field> for &'a $field {
+            type Output = $field;
+
+            #[inline]
+            fn sub(self, rhs: &'b $field) -> $field {
+                self.sub(rhs)
+            }
+        }
+
+        impl<'a, 'b> Add<&'b $field> for &'a $field {
+            type Output = $field;
+
+            #[inline]
+            fn add(self, rhs: &'b $field) -> $field {
+                self.add(rhs)
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $field> for &'a $field {
+            type Output = $field;
+
+            #[inline]
+            fn mul(self, rhs: &'b $field) -> $field {
+                self.mul(rhs)
+            }
+        }
+
+        impl_binops_additive!($field, $field);
+        impl_binops_multiplicative!($field, $field);
+
+        impl Default for $field {
+            #[inline]
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        impl $field {
+            /// Returns zero, the additive identity.
+            #[inline]
+            pub const fn zero() -> $field {
+                $field([0, 0, 0, 0])
+            }
+
+            /// Returns one, the multiplicative identity.
+            #[inline]
+            pub const fn one() -> $field {
+                $r
+            }
+
+            /// Doubles this field element.
+            #[inline]
+            pub const fn double(&self) -> $field {
+                // Shift left by one bit, keeping the top bit in `carry`.
+                let d0 = self.0[0] << 1;
+                let d1 = (self.0[1] << 1) | (self.0[0] >> 63);
+                let d2 = (self.0[2] << 1) | (self.0[1] >> 63);
+                let d3 = (self.0[3] << 1) | (self.0[2] >> 63);
+                let carry = self.0[3] >> 63;
+
+                // Attempt to subtract the modulus, as in `add`.
+                let (d0, borrow) = sbb(d0, $modulus.0[0], 0);
+                let (d1, borrow) = sbb(d1, $modulus.0[1], borrow);
+                let (d2, borrow) = sbb(d2, $modulus.0[2], borrow);
+                let (d3, borrow) = sbb(d3, $modulus.0[3], borrow);
+                let (_, borrow) = sbb(carry, 0, borrow);
+
+                let (d0, carry) = adc(d0, $modulus.0[0] & borrow, 0);
+                let (d1, carry) = adc(d1, $modulus.0[1] & borrow, carry);
+                let (d2, carry) = adc(d2, $modulus.0[2] & borrow, carry);
+                let (d3, _) = adc(d3, $modulus.0[3] & borrow, carry);
+
+                $field([d0, d1, d2, d3])
+            }
+
+            /// Multiplies this field element by three with a doubling and an
+            /// addition.
+            #[inline]
+            pub const fn mul3(&self) -> $field {
+                $field::add(&$field::double(self), self)
+            }
+
+            /// Multiplies this field element by eight with three doublings.
+            #[inline]
+            pub const fn mul8(&self) -> $field {
+                $field::double(&$field::double(&$field::double(self)))
+            }
+
+            /// Multiplies this field element by the small constant `k` with
+            /// doublings and additions, running in time that depends only on
+            /// `k`. For constants with more than a few bits set `mul_by_u64`
+            /// is cheaper.
+            pub const fn mul_small(&self, k: u64) -> $field {
+                let mut res = $field::zero();
+                let mut i = 64 - k.leading_zeros();
+                while i > 0 {
+                    i -= 1;
+                    res = $field::double(&res);
+                    if (k >> i) & 1 == 1 {
+                        res = $field::add(&res, self);
+                    }
+                }
+                res
+            }
+
+            /// Reduces the 512-bit little-endian integer with limbs `limbs`
+            /// modulo the modulus, e.g. to map a wide hash output or 64 random
+            /// bytes to a nearly uniform element.
+            pub const fn from_u512(limbs: [u64; 8]) -> $field {
+                // We reduce an arbitrary 512-bit number by decomposing it into two 256-bit digits
+                // with the higher bits multiplied by 2^256. Thus, we perform two reductions
+                //
+                // 1. the lower bits are multiplied by R^2, as normal
+                // 2. the upper bits are multiplied by R^2 * 2^256 = R^3
+                //
+                // and computing their sum in the field. It remains to see that arbitrary 256-bit
+                // numbers can be placed into Montgomery form safely using the reduction. The
+                // reduction works so long as the product is less than R=2^256 multiplied by
+                // the modulus. This holds because for any `c` smaller than the modulus, we have
+                // that (2^256 - 1)*c is an acceptable product for the reduction. Therefore, the
+                // reduction always works so long as `c` is in the field; in this case it is either the
+                // constant `R2` or `R3`.
+                let d0 = $field([limbs[0], limbs[1], limbs[2], limbs[3]]);
+                let d1 = $field([limbs[4], limbs[5], limbs[6], limbs[7]]);
+                // Convert to Montgomery form
+                $field::add(&$field::mul(&d0, &$r2), &$field::mul(&d1, &$r3))
+            }
+
+            /// Reduces the 512-bit little-endian integer `bytes` modulo the
+            /// modulus, as `from_u512` does.
+            pub const fn from_bytes_wide(bytes: &[u8; 64]) -> $field {
+                let mut limbs = [0u64; 8];
+                let mut i = 0;
+                while i < 64 {
+                    limbs[i / 8] |= (bytes[i] as u64) << (8 * (i % 8));
+                    i += 1;
+                }
+                $field::from_u512(limbs)
+            }
+
+            /// Converts from an integer represented in little endian
+            /// into its (congruent) field element representation.
+            pub const fn from_raw(val: [u64; 4]) -> Self {
+                (&$field(val)).mul(&$r2)
+            }
+
+            /// Parses the big-endian hex integer `hex`, with an optional `0x`
+            /// prefix and at most 64 digits, into a field element. This is
+            /// meant for constants, and fails to compile in a const context if
+            /// `hex` is invalid.
+            ///
+            /// Panics if `hex` has no digits or a character that is not a hex
+            /// digit, or if it is not less than the modulus.
+            pub const fn from_hex(hex: &str) -> Self {
+                const INVALID: [$field; 0] = [];
+
+                let limbs = crate::arithmetic::hex_to_limbs(hex);
+                let (_, borrow) = sbb(limbs[0], $modulus.0[0], 0);
+                let (_, borrow) = sbb(limbs[1], $modulus.0[1], borrow);
+                let (_, borrow) = sbb(limbs[2], $modulus.0[2], borrow);
+                let (_, borrow) = sbb(limbs[3], $modulus.0[3], borrow);
+                if borrow == 0 {
+                    // Fail as `hex_to_limbs` does on invalid digits.
+                    return INVALID[limbs[0] as usize];
+                }
+                $field::from_raw(limbs)
+            }
+
+            /// Returns the little-endian limbs of the internal Montgomery form
+            /// of this element, $a R$ modulo the modulus.
+            ///
+            /// This exposes the internal representation, which may change
+            /// between versions; use `to_repr` for a portable encoding.
+            pub const fn to_montgomery_limbs(&self) -> [u64; 4] {
+                self.0
+            }
+
+            /// Returns the element whose internal Montgomery form has the
+            /// little-endian limbs `limbs`, failing if they are not less than
+            /// the modulus. This is the inverse of `to_montgomery_limbs`.
+            ///
+            /// This depends on the internal representation, which may change
+            /// between versions; use `from_repr` for a portable encoding.
+            pub fn from_montgomery_limbs(limbs: [u64; 4]) -> CtOption<Self> {
+                let (_, borrow) = sbb(limbs[0], $modulus.0[0], 0);
+                let (_, borrow) = sbb(limbs[1], $modulus.0[1], borrow);
+                let (_, borrow) = sbb(limbs[2], $modulus.0[2], borrow);
+                let (_, borrow) = sbb(limbs[3], $modulus.0[3], borrow);
+
+                // The limbs are less than the modulus exactly when the
+                // subtraction underflows.
+                CtOption::new($field(limbs), Choice::from((borrow as u8) & 1))
+            }
+
+            /// Returns the element whose internal Montgomery form has the
+            /// little-endian limbs `limbs`, without checking them. This is the
+            /// inverse of `to_montgomery_limbs`, for reinterpreting limbs that
+            /// came from it, e.g. after a round trip through a GPU buffer.
+            ///
+            /// The limbs must be less than the modulus. Every operation on the
+            /// element assumes this, and its results are unspecified otherwise;
+            /// use `from_montgomery_limbs` for limbs that are not trusted. Like
+            /// the internal representation, this may change between versions.
+            pub const fn from_montgomery_unchecked(limbs: [u64; 4]) -> Self {
+                $field(limbs)
+            }
+
+            /// Returns the little-endian limbs of the canonical integer
+            /// representing `self`, which is less than the modulus. Unlike
+            /// `to_montgomery_limbs`, this does not depend on the internal
+            /// representation.
+            pub const fn to_canonical_limbs(&self) -> [u64; 4] {
+                // (a R) / R = a
+                $field::montgomery_reduce(self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0).0
+            }
+
+            /// Obtains a field element congruent to the integer `v`.
+            pub const fn from_u128(v: u128) -> Self {
+                $field::from_raw([v as u64, (v >> 64) as u64, 0, 0])
+            }
+
+            /// Gets the lower 128 bits of this field element when expressed
+            /// canonically.
+            pub const fn get_lower_128(&self) -> u128 {
+                let limbs = self.to_canonical_limbs();
+                (limbs[0] as u128) | ((limbs[1] as u128) << 64)
+            }
+
+            /// Replaces every nonzero element of `elements` with its inverse
+            /// using a single inversion, leaving zeros unchanged, and returns
+            /// whether all the elements were nonzero.
+            ///
+            /// The scratch space is an array of the same size on the stack,
+            /// so this needs no allocator. This is constant time.
+            pub fn batch_invert_in_place<const N: usize>(elements: &mut [Self; N]) -> Choice {
+                let mut products = [$field::zero(); N];
+                Self::batch_invert_with_scratch(elements, &mut products)
+            }
+
+            /// Replaces every nonzero element of `elements` with its inverse
+            /// using Montgomery's trick, which costs a single inversion and
+            /// three multiplications per element, leaving zeros unchanged.
+            /// Returns whether all the elements were nonzero. This is constant
+            /// time.
+            #[cfg(feature = "std")]
+            pub fn batch_invert(elements: &mut [Self]) -> Choice {
+                let mut products = vec![$field::zero(); elements.len()];
+                Self::batch_invert_with_scratch(elements, &mut products)
+            }
+
+            /// Returns the inverses of `elements` as computed by
+            /// `batch_invert`, with zero for every zero element.
+            #[cfg(feature = "std")]
+            pub fn batch_inverted(elements: &[Self]) -> std::vec::Vec<Self> {
+                let mut res = elements.to_vec();
+                Self::batch_invert(&mut res);
+                res
+            }
+
+            /// Inverts `elements` in place as `batch_invert` does, and returns
+            /// for each element whether it was zero and so left unchanged.
+            /// This is constant time.
+            #[cfg(feature = "std")]
+            pub fn batch_invert_with_zero_mask(elements: &mut [Self]) -> std::vec::Vec<Choice> {
+                let zero = $field::zero();
+                let zero_mask = elements
+                    .iter()
+                    .map(|element| element.ct_eq(&zero))
+                    .collect();
+                Self::batch_invert(elements);
+                zero_mask
+            }
+
+            /// Batch inversion with `products`, of the same length as
+            /// `elements`, as scratch space.
+            fn batch_invert_with_scratch(elements: &mut [Self], products: &mut [Self]) -> Choice {
+                let zero = $field::zero();
+                let mut acc = $field::one();
+                let mut all_nonzero = Choice::from(1);
+                for (element, product) in elements.iter().zip(products.iter_mut()) {
+                    // products[i] is the product of the nonzero elements
+                    // before elements[i].
+                    *product = acc;
+                    let is_zero = element.ct_eq(&zero);
+                    all_nonzero &= !is_zero;
+                    acc = $field::conditional_select(&(acc * element), &acc, is_zero);
+                }
+
+                // acc is a product of nonzero elements, so it is invertible.
+                acc = ff::Field::invert(&acc).unwrap();
+
+                for (element, product) in elements.iter_mut().zip(products.iter()).rev() {
+                    let is_zero = element.ct_eq(&zero);
+                    let inverse = acc * product;
+                    acc = $field::conditional_select(&(acc * *element), &acc, is_zero);
+                    *element = $field::conditional_select(&inverse, &zero, is_zero);
+                }
+                all_nonzero
+            }
+
+            /// $(m - 1) / 2$, the exponent of Euler's criterion.
+            const LEGENDRE_EXPONENT: [u64; 4] = [
+                ($modulus.0[0] >> 1) | ($modulus.0[1] << 63),
+                ($modulus.0[1] >> 1) | ($modulus.0[2] << 63),
+                ($modulus.0[2] >> 1) | ($modulus.0[3] << 63),
+                $modulus.0[3] >> 1,
+            ];
+
+            /// Returns $a^{(m - 1) / 2}$ for this element $a$, which is zero, one
+            /// or minus one by Euler's criterion.
+            fn euler_criterion(&self) -> Self {
+                ff::Field::pow_vartime(self, &Self::LEGENDRE_EXPONENT)
+            }
+
+            /// Returns the Legendre symbol of this element.
+            ///
+            /// The exponentiation is constant time, but branching on the
+            /// result is not; use `is_square` for secret elements.
+            pub fn legendre(&self) -> $crate::arithmetic::LegendreSymbol {
+                let s = self.euler_criterion();
+                if bool::from(s.ct_eq(&$field::zero())) {
+                    $crate::arithmetic::LegendreSymbol::Zero
+                } else if bool::from(s.ct_eq(&$field::one())) {
+                    $crate::arithmetic::LegendreSymbol::QuadraticResidue
+                } else {
+                    $crate::arithmetic::LegendreSymbol::QuadraticNonResidue
+                }
+            }
+
+            /// Returns whether this element is a square, counting zero as a
+            /// square. This is cheaper than attempting `sqrt`, and constant
+            /// time.
+            pub fn is_square(&self) -> Choice {
+                let s = self.euler_criterion();
+                s.ct_eq(&$field::zero()) | s.ct_eq(&$field::one())
+            }
+
+            /// Computes the multiplicative inverse of this element, failing if
+            /// the element is zero. This is not constant time; use `invert`
+            /// for secret elements.
+            pub fn invert_vartime(&self) -> CtOption<Self> {
+                if bool::from(self.ct_eq(&$field::zero())) {
+                    return CtOption::new($field::zero(), Choice::from(0u8));
+                }
+
+                // Leave Montgomery form, invert the integer and come back.
+                let a = $field::montgomery_reduce(
+                    self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0,
+                );
+                let inv = $crate::arithmetic::invert_vartime_limbs(&a.0, &$modulus.0);
+                CtOption::new($field::from_raw(inv), Choice::from(1u8))
+            }
+
+            /// Squares this element.
+            #[cfg_attr(not(feature = "opt-size"), inline)]
+            #[cfg_attr(feature = "opt-size", inline(never))]
+            pub const fn square(&self) -> $field {
+                let (r1, carry) = mac(0, self.0[0], self.0[1], 0);
+                let (r2, carry) = mac(0, self.0[0], self.0[2], carry);
+                let (r3, r4) = mac(0, self.0[0], self.0[3], carry);
+
+                let (r3, carry) = mac(r3, self.0[1], self.0[2], 0);
+                let (r4, r5) = mac(r4, self.0[1], self.0[3], carry);
+
+                let (r5, r6) = mac(r5, self.0[2], self.0[3], 0);
+
+                let r7 = r6 >> 63;
+                let r6 = (r6 << 1) | (r5 >> 63);
+                let r5 = (r5 << 1) | (r4 >> 63);
+                let r4 = (r4 << 1) | (r3 >> 63);
+                let r3 = (r3 << 1) | (r2 >> 63);
+                let r2 = (r2 << 1) | (r1 >> 63);
+                let r1 = r1 << 1;
+
+                let (r0, carry) = mac(0, self.0[0], self.0[0], 0);
+                let (r1, carry) = adc(0, r1, carry);
+                let (r2, carry) = mac(r2, self.0[1], self.0[1], carry);
+                let (r3, carry) = adc(0, r3, carry);
+                let (r4, carry) = mac(r4, self.0[2], self.0[2], carry);
+                let (r5, carry) = adc(0, r5, carry);
+                let (r6, carry) = mac(r6, self.0[3], self.0[3], carry);
+                let (r7, _) = adc(0, r7, carry);
+
+                $field::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
+            }
+
+            /// Exponentiates `self` by `exp`, a little-endian integer.
+            ///
+            /// This is constant time in both `self` and `exp`, so unlike
+            /// `pow_vartime` it may be used when either is secret. It uses
+            /// fixed 4-bit windows, reading each one from a table of the
+            /// first 16 powers of `self` with a constant-time lookup.
+            pub fn pow(&self, exp: &[u64; 4]) -> Self {
+                let mut table = [Self::one(); 16];
+                let mut acc = Self::one();
+                for entry in table.iter_mut().skip(1) {
+                    acc *= self;
+                    *entry = acc;
+                }
+
+                let mut res = Self::one();
+                for limb in exp.iter().rev() {
+                    for i in (0..16).rev() {
+                        res = res.square().square().square().square();
+
+                        let window = (limb >> (4 * i)) & 0xf;
+                        let mut power = Self::one();
+                        for (j, entry) in table.iter().enumerate() {
+                            power.conditional_assign(entry, (j as u64).ct_eq(&window));
+                        }
+                        res *= power;
+                    }
+                }
+                res
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            #[cfg_attr(not(feature = "opt-size"), inline(always))]
+            #[cfg_attr(feature = "opt-size", inline(never))]
+            const fn montgomery_reduce(
+                r0: u64,
+                r1: u64,
+                r2: u64,
+                r3: u64,
+                r4: u64,
+                r5: u64,
+                r6: u64,
+                r7: u64,
+            ) -> Self {
+                $field(crate::arithmetic::$reduce(
+                    &[r0, r1, r2, r3, r4, r5, r6, r7],
+                    &$modulus.0,
+                    $inv,
+                ))
+            }
+
+            /// Multiplies `rhs` by `self`, returning the result.
+            #[cfg_attr(not(feature = "opt-size"), inline)]
+            #[cfg_attr(feature = "opt-size", inline(never))]
+            pub const fn mul(&self, rhs: &Self) -> Self {
+                // Schoolbook multiplication
+
+                let (r0, carry) = mac(0, self.0[0], rhs.0[0], 0);
+                let (r1, carry) = mac(0, self.0[0], rhs.0[1], carry);
+                let (r2, carry) = mac(0, self.0[0], rhs.0[2], carry);
+                let (r3, r4) = mac(0, self.0[0], rhs.0[3], carry);
+
+                let (r1, carry) = mac(r1, self.0[1], rhs.0[0], 0);
+                let (r2, carry) = mac(r2, self.0[1], rhs.0[1], carry);
+                let (r3, carry) = mac(r3, self.0[1], rhs.0[2], carry);
+                let (r4, r5) = mac(r4, self.0[1], rhs.0[3], carry);
+
+                let (r2, carry) = mac(r2, self.0[2], rhs.0[0], 0);
+                let (r3, carry) = mac(r3, self.0[2], rhs.0[1], carry);
+                let (r4, carry) = mac(r4, self.0[2], rhs.0[2], carry);
+                let (r5, r6) = mac(r5, self.0[2], rhs.0[3], carry);
+
+                let (r3, carry) = mac(r3, self.0[3], rhs.0[0], 0);
+                let (r4, carry) = mac(r4, self.0[3], rhs.0[1], carry);
+                let (r5, carry) = mac(r5, self.0[3], rhs.0[2], carry);
+                let (r6, r7) = mac(r6, self.0[3], rhs.0[3], carry);
+
+                $field::montgomery_reduce(r0, r1, r2, r3, r4, r5, r6, r7)
+            }
+
+            /// Multiplies `self` by the integer `rhs`, without first converting
+            /// `rhs` into Montgomery form.
+            #[inline]
+            pub const fn mul_by_u64(&self, rhs: u64) -> Self {
+                let (d0, carry) = mac(0, self.0[0], rhs, 0);
+                let (d1, carry) = mac(0, self.0[1], rhs, carry);
+                let (d2, carry) = mac(0, self.0[2], rhs, carry);
+                let (d3, d4) = mac(0, self.0[3], rhs, carry);
+
+                $field::reduce_320(d0, d1, d2, d3, d4)
+            }
+
+            /// Multiplies `self` by the integer `rhs`, without first converting
+            /// `rhs` into Montgomery form.
+            #[inline]
+            pub const fn mul_by_u128(&self, rhs: u128) -> Self {
+                let lo = self.mul_by_u64(rhs as u64);
+                let hi = self.mul_by_u64((rhs >> 64) as u64);
+
+                // hi * 2^64
+                let hi = $field::reduce_320(0, hi.0[0], hi.0[1], hi.0[2], hi.0[3]);
+
+                $field::add(&lo, &hi)
+            }
+
+            /// Reduces the 320-bit integer `d0..d4` modulo the modulus.
+            #[inline(always)]
+            const fn reduce_320(d0: u64, d1: u64, d2: u64, d3: u64, d4: u64) -> Self {
+                // 2^256 = R mod m, so fold the top limb back in as d4 * R. The
+                // carry out of that is at most one, and folding it in again
+                // cannot carry since the low limbs are then below 2^192.
+                let (d0, carry) = mac(d0, d4, $r.0[0], 0);
+                let (d1, carry) = mac(d1, d4, $r.0[1], carry);
+                let (d2, carry) = mac(d2, d4, $r.0[2], carry);
+                let (d3, d4) = mac(d3, d4, $r.0[3], carry);
+
+                let (d0, carry) = mac(d0, d4, $r.0[0], 0);
+                let (d1, carry) = mac(d1, d4, $r.0[1], carry);
+                let (d2, carry) = mac(d2, d4, $r.0[2], carry);
+                let (d3, _) = mac(d3, d4, $r.0[3], carry);
+
+                // The result is below 2^256 < 2m; subtract the modulus once.
+                let (d0, borrow) = sbb(d0, $modulus.0[0], 0);
+                let (d1, borrow) = sbb(d1, $modulus.0[1], borrow);
+                let (d2, borrow) = sbb(d2, $modulus.0[2], borrow);
+                let (d3, borrow) = sbb(d3, $modulus.0[3], borrow);
+
+                let (d0, carry) = adc(d0, $modulus.0[0] & borrow, 0);
+                let (d1, carry) = adc(d1, $modulus.0[1] & borrow, carry);
+                let (d2, carry) = adc(d2, $modulus.0[2] & borrow, carry);
+                let (d3, _) = adc(d3, $modulus.0[3] & borrow, carry);
+
+                $field([d0, d1, d2, d3])
+            }
+
+            /// Subtracts `rhs` from `self`, returning the result.
+            #[inline]
+            pub const fn sub(&self, rhs: &Self) -> Self {
+                let (d0, borrow) = sbb(self.0[0], rhs.0[0], 0);
+                let (d1, borrow) = sbb(self.0[1], rhs.0[1], borrow);
+                let (d2, borrow) = sbb(self.0[2], rhs.0[2], borrow);
+                let (d3, borrow) = sbb(self.0[3], rhs.0[3], borrow);
+
+                // If underflow occurred on the final limb, borrow = 0xfff...fff, otherwise
+                // borrow = 0x000...000. Thus, we use it as a mask to conditionally add the modulus.
+                let (d0, carry) = adc(d0, $modulus.0[0] & borrow, 0);
+                let (d1, carry) = adc(d1, $modulus.0[1] & borrow, carry);
+                let (d2, carry) = adc(d2, $modulus.0[2] & borrow, carry);
+                let (d3, _) = adc(d3, $modulus.0[3] & borrow, carry);
+
+                $field([d0, d1, d2, d3])
+            }
+
+            /// Adds `rhs` to `self`, returning the result.
+            #[inline]
+            pub const fn add(&self, rhs: &Self) -> Self {
+                let (d0, carry) = adc(self.0[0], rhs.0[0], 0);
+                let (d1, carry) = adc(self.0[1], rhs.0[1], carry);
+                let (d2, carry) = adc(self.0[2], rhs.0[2], carry);
+                let (d3, carry) = adc(self.0[3], rhs.0[3], carry);
+
+                // Attempt to subtract the modulus, to ensure the value
+                // is smaller than the modulus.
+                let (d0, borrow) = sbb(d0, $modulus.0[0], 0);
+                let (d1, borrow) = sbb(d1, $modulus.0[1], borrow);
+                let (d2, borrow) = sbb(d2, $modulus.0[2], borrow);
+                let (d3, borrow) = sbb(d3, $modulus.0[3], borrow);
+                let (_, borrow) = sbb(carry, 0, borrow);
+
+                let (d0, carry) = adc(d0, $modulus.0[0] & borrow, 0);
+                let (d1, carry) = adc(d1, $modulus.0[1] & borrow, carry);
+                let (d2, carry) = adc(d2, $modulus.0[2] & borrow, carry);
+                let (d3, _) = adc(d3, $modulus.0[3] & borrow, carry);
+
+                $field([d0, d1, d2, d3])
+            }
+
+            /// Negates `self`.
+            #[inline]
+            pub const fn neg(&self) -> Self {
+                // Subtract `self` from `MODULUS` to negate. Ignore the final
+                // borrow because it cannot underflow; self is guaranteed to
+                // be in the field.
+                let (d0, borrow) = sbb($modulus.0[0], self.0[0], 0);
+                let (d1, borrow) = sbb($modulus.0[1], self.0[1], borrow);
+                let (d2, borrow) = sbb($modulus.0[2], self.0[2], borrow);
+                let (d3, _) = sbb($modulus.0[3], self.0[3], borrow);
+
+                // `tmp` could be `MODULUS` if `self` was zero. Create a mask that is
+                // zero if `self` was zero, and `u64::max_value()` if self was nonzero.
+                let mask =
+                    (((self.0[0] | self.0[1] | self.0[2] | self.0[3]) == 0) as u64).wrapping_sub(1);
+
+                $field([d0 & mask, d1 & mask, d2 & mask, d3 & mask])
+            }
+
+            /// Parses the integer `digits` in the radix `radix`, with no sign
+            /// or prefix, failing if it is not less than the modulus. This is
+            /// not constant time.
+            ///
+            /// Panics if `radix` is not between 2 and 36.
+            pub fn from_str_radix(
+                digits: &str,
+                radix: u32,
+            ) -> Result<Self, crate::fields::ParseFieldError> {
+                let limbs = crate::fields::parse_limbs(digits, radix)?;
+                let (_, borrow) = sbb(limbs[0], $modulus.0[0], 0);
+                let (_, borrow) = sbb(limbs[1], $modulus.0[1], borrow);
+                let (_, borrow) = sbb(limbs[2], $modulus.0[2], borrow);
+                let (_, borrow) = sbb(limbs[3], $modulus.0[3], borrow);
+                if borrow == 0 {
+                    return Err(crate::fields::ParseFieldError::OutOfRange);
+                }
+                Ok($field::from_raw(limbs))
+            }
+
+            /// Decodes the big-endian encoding of an element, the reverse of
+            /// `to_repr`, failing if `bytes` is not 32 bytes long or is not
+            /// canonical.
+            pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, crate::fields::FieldBytesError> {
+                let mut repr: [u8; 32] = bytes.try_into().map_err(|_| {
+                    crate::fields::FieldBytesError::InvalidLength {
+                        expected: 32,
+                        actual: bytes.len(),
+                    }
+                })?;
+                repr.reverse();
+                Option::from($field::from_repr(repr))
+                    .ok_or(crate::fields::FieldBytesError::NonCanonical)
+            }
+
+            /// Decodes the 32-byte big-endian encoding of an element, the
+            /// reverse of `to_bytes_be`, failing if it is not canonical.
+            pub fn from_repr_be(bytes: &[u8; 32]) -> CtOption<Self> {
+                let mut repr = *bytes;
+                repr.reverse();
+                $field::from_repr(repr)
+            }
+
+            /// Returns the canonical big-endian encoding of `self`, as used by
+            /// SEC1 and BIP-340.
+            pub fn to_bytes_be(&self) -> [u8; 32] {
+                let mut res = self.to_repr();
+                res.reverse();
+                res
+            }
+
+            /// Returns the bits of the canonical representation of `self`,
+            /// least significant first. Unlike `to_le_bits`, this does not
+            /// need the `bits` feature.
+            pub fn to_le_bits_array(&self) -> [bool; 256] {
+                let mut res = [false; 256];
+                for (bit, b) in res.iter_mut().zip(self.le_bits_iter()) {
+                    *bit = b;
+                }
+                res
+            }
+
+            /// Returns an iterator over the bits of the canonical
+            /// representation of `self`, least significant first.
+            pub fn le_bits_iter(&self) -> impl Iterator<Item = bool> {
+                let repr = self.to_repr();
+                (0..256).map(move |i| (repr[i / 8] >> (i % 8)) & 1 == 1)
+            }
+        }
+
+        #[cfg(feature = "quickcheck")]
+        impl quickcheck::Arbitrary for $field {
+            /// Returns a uniformly distributed element, or one of the edge
+            /// cases zero, one and minus one with probability 1/8.
+            fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+                if u8::arbitrary(g) < 32 {
+                    return *g
+                        .choose(&[Self::zero(), Self::one(), -Self::one()])
+                        .unwrap();
+                }
+                Self::from_u512([
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                    u64::arbitrary(g),
+                ])
+            }
+        }
+
+        impl core::str::FromStr for $field {
+            type Err = crate::fields::ParseFieldError;
+
+            /// Parses a `0x`-prefixed hex integer or, without the prefix, a
+            /// decimal one as `from_str_vartime` does. Unlike
+            /// `from_str_vartime`, integers that are not less than the
+            /// modulus are rejected rather than reduced.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.strip_prefix("0x") {
+                    Some(hex) => $field::from_str_radix(hex, 16),
+                    None => $field::from_str_radix(s, 10),
+                }
+            }
+        }
+
+        impl<'a> core::convert::TryFrom<&'a [u8]> for $field {
+            type Error = crate::fields::FieldBytesError;
+
+            /// Decodes the little-endian encoding of `to_repr`, failing if
+            /// `bytes` is not 32 bytes long or is not canonical.
+            fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+                let repr: [u8; 32] = bytes.try_into().map_err(|_| {
+                    crate::fields::FieldBytesError::InvalidLength {
+                        expected: 32,
+                        actual: bytes.len(),
+                    }
+                })?;
+                Option::from($field::from_repr(repr))
+                    .ok_or(crate::fields::FieldBytesError::NonCanonical)
+            }
+        }
+
+        impl From<$field> for [u8; 32] {
+            fn from(value: $field) -> [u8; 32] {
+                value.to_repr()
+            }
+        }
+
+        impl<'a> From<&'a $field> for [u8; 32] {
+            fn from(value: &'a $field) -> [u8; 32] {
+                value.to_repr()
+            }
+        }
+
+        impl Group for $field {
+            type Scalar = $field;
+
+            fn group_zero() -> Self {
+                Self::zero()
+            }
+            fn group_add(&mut self, rhs: &Self) {
+                *self += *rhs;
+            }
+            fn group_sub(&mut self, rhs: &Self) {
+                *self -= *rhs;
+            }
+            fn group_scale(&mut self, by: &Self::Scalar) {
+                *self *= *by;
+            }
+        }
+    };
+}
+
+/// Evaluates to the [`Fq`](crate::Fq) element given by a big-endian hex
+/// string literal, with an optional `0x` prefix, at compile time. Fails to
+/// compile if the string is not a hex integer less than the modulus.
+///
+/// ```
+/// use secp256k1forhalo2::{fq, Fq};
+///
+/// const SEVEN: Fq = fq!("0x07");
+/// assert_eq!(SEVEN, Fq::from(7));
+/// ```
+#[macro_export]
+macro_rules! fq {
+    ($hex:expr) => {{
+        const VALUE: $crate::Fq = $crate::Fq::from_hex($hex);
+        VALUE
+    }};
+}
+
+/// Evaluates to the [`Fp`](crate::Fp) element given by a big-endian hex
+/// string literal, with an optional `0x` prefix, at compile time. Fails to
+/// compile if the string is not a hex integer less than the modulus.
+///
+/// ```
+/// use secp256k1forhalo2::{fp, Fp};
+///
+/// const SEVEN: Fp = fp!("0x07");
+/// assert_eq!(SEVEN, Fp::from(7));
+/// ```
+#[macro_export]
+macro_rules! fp {
+    ($hex:expr) => {{
+        const VALUE: $crate::Fp = $crate::Fp::from_hex($hex);
+        VALUE
+    }};
+}
+
+/// Implements `serde::Serialize` and `serde::Deserialize` for a prime field
+/// with a 32-byte little-endian `Repr`.
+///
+/// Human-readable formats use the `0x`-prefixed big-endian hex string printed
+/// by `Debug`, and binary formats use the 32 bytes of `to_repr` as a tuple.
+/// Deserializing rejects encodings of integers that are not reduced modulo
+/// the field modulus.
+#[cfg(feature = "serde")]
+macro_rules! field_serde_impl {
+    ($field:ident) => {
+        impl ::serde::Serialize for $field {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let repr = self.to_repr();
+                if serializer.is_human_readable() {
+                    const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+                    let mut hex = [0u8; 66];
+                    hex[..2].copy_from_slice(b"0x");
+                    for (pair, &b) in hex[2..].chunks_mut(2).zip(repr.iter().rev()) {
+                        pair[0] = HEX_DIGITS[(b >> 4) as usize];
+                        pair[1] = HEX_DIGITS[(b & 0xf) as usize];
+                    }
+                    serializer.serialize_str(core::str::from_utf8(&hex).unwrap())
+                } else {
+                    use ::serde::ser::SerializeTuple;
+
+                    let mut tuple = serializer.serialize_tuple(32)?;
+                    for b in repr.iter() {
+                        tuple.serialize_element(b)?;
+                    }
+                    tuple.end()
+                }
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $field {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                use ::serde::de::{Error, SeqAccess, Unexpected, Visitor};
+
+                const EXPECTED: &str = "a canonical field element";
+
+                fn hex_digit(c: u8) -> Option<u8> {
+                    match c {
+                        b'0'..=b'9' => Some(c - b'0'),
+                        b'a'..=b'f' => Some(c - b'a' + 10),
+                        b'A'..=b'F' => Some(c - b'A' + 10),
+                        _ => None,
+                    }
+                }
+
+                struct HexVisitor;
+
+                impl<'de> Visitor<'de> for HexVisitor {
+                    type Value = $field;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "a 0x-prefixed string of 64 hex digits")
+                    }
+
+                    fn visit_str<E: Error>(self, v: &str) -> Result<$field, E> {
+                        let invalid = || E::invalid_value(Unexpected::Str(v), &self);
+                        let hex = v
+                            .strip_prefix("0x")
+                            .filter(|hex| hex.len() == 64)
+                            .ok_or_else(invalid)?;
+
+                        let mut repr = [0u8; 32];
+                        for (b, pair) in repr.iter_mut().rev().zip(hex.as_bytes().chunks(2)) {
+                            let hi = hex_digit(pair[0]).ok_or_else(invalid)?;
+                            let lo = hex_digit(pair[1]).ok_or_else(invalid)?;
+                            *b = hi << 4 | lo;
+                        }
+                        Option::from($field::from_repr(repr))
+                            .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &EXPECTED))
+                    }
+                }
+
+                struct BytesVisitor;
+
+                impl<'de> Visitor<'de> for BytesVisitor {
+                    type Value = $field;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "32 bytes")
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<$field, A::Error> {
+                        let mut repr = [0u8; 32];
+                        for (i, b) in repr.iter_mut().enumerate() {
+                            *b = seq
+                                .next_element()?
+                                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                        }
+                        Option::from($field::from_repr(repr)).ok_or_else(|| {
+                            A::Error::invalid_value(Unexpected::Bytes(&repr), &EXPECTED)
+                        })
+                    }
+                }
+
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(HexVisitor)
+                } else {
+                    deserializer.deserialize_tuple(32, BytesVisitor)
+                }
+            }
+        }
+    };
+}
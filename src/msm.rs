@@ -0,0 +1,319 @@
+//! This module contains multi-scalar multiplication over the secp256k1 curve.
+
+use core::borrow::Borrow;
+use std::vec::Vec;
+
+use ff::PrimeField;
+use group::{Curve, Group as _};
+
+use super::{Fq, Secp256k1, Secp256k1Affine};
+
+/// Computes $\sum_i s_i \cdot P_i$ for the given scalars and affine bases.
+///
+/// The bases are consumed in affine form and only ever combined with mixed
+/// addition, so no projective copy of `bases` is made. This is not constant
+/// time.
+///
+/// Panics if `scalars` and `bases` have different lengths.
+pub fn msm(scalars: &[Fq], bases: &[Secp256k1Affine]) -> Secp256k1 {
+    assert_eq!(scalars.len(), bases.len());
+
+    let scalars: Vec<_> = scalars.iter().map(|s| s.to_repr()).collect();
+    let mut acc = Secp256k1::identity();
+    multiexp_serial(&scalars, bases, &mut acc);
+    acc
+}
+
+/// Computes $\sum_i s_i \cdot P_i$ over an iterator of `(scalar, base)` pairs.
+///
+/// The pairs are consumed in chunks of at most `chunk_size` elements, so the
+/// bases never need to be materialized all at once. This is not constant time.
+///
+/// Panics if `chunk_size` is zero.
+pub fn msm_iter<I>(pairs: I, chunk_size: usize) -> Secp256k1
+where
+    I: IntoIterator<Item = (Fq, Secp256k1Affine)>,
+{
+    assert!(chunk_size > 0);
+
+    let mut acc = Secp256k1::identity();
+    let mut scalars = Vec::with_capacity(chunk_size);
+    let mut bases = Vec::with_capacity(chunk_size);
+    for (scalar, base) in pairs {
+        scalars.push(scalar.to_repr());
+        bases.push(base);
+        if bases.len() == chunk_size {
+            multiexp_serial(&scalars, &bases, &mut acc);
+            scalars.clear();
+            bases.clear();
+        }
+    }
+    if !bases.is_empty() {
+        multiexp_serial(&scalars, &bases, &mut acc);
+    }
+
+    acc
+}
+
+/// Multi-scalar multiplication that is constant time in the scalars and
+/// points, in the shape of `curve25519_dalek::traits::MultiscalarMul`.
+pub trait MultiscalarMul {
+    /// The type of point being multiplied.
+    type Point;
+
+    /// Computes $\sum_i s_i \cdot P_i$ for the given `scalars` and `points`.
+    ///
+    /// Panics if `scalars` and `points` have different lengths.
+    fn multiscalar_mul<I, J>(scalars: I, points: J) -> Self::Point
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Fq>,
+        J: IntoIterator,
+        J::Item: Borrow<Self::Point>;
+}
+
+/// Variable-time multi-scalar multiplication, in the shape of
+/// `curve25519_dalek::traits::VartimeMultiscalarMul`.
+pub trait VartimeMultiscalarMul {
+    /// The type of point being multiplied.
+    type Point: Clone;
+
+    /// Computes $\sum_i s_i \cdot P_i$ for the given `scalars` and `points`,
+    /// returning `None` if any of the points is `None`. This is not constant
+    /// time.
+    ///
+    /// Panics if `scalars` and `points` have different lengths.
+    fn optional_multiscalar_mul<I, J>(scalars: I, points: J) -> Option<Self::Point>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Fq>,
+        J: IntoIterator<Item = Option<Self::Point>>;
+
+    /// Computes $\sum_i s_i \cdot P_i$ for the given `scalars` and `points`.
+    /// This is not constant time.
+    ///
+    /// Panics if `scalars` and `points` have different lengths.
+    fn vartime_multiscalar_mul<I, J>(scalars: I, points: J) -> Self::Point
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Fq>,
+        J: IntoIterator,
+        J::Item: Borrow<Self::Point>,
+    {
+        Self::optional_multiscalar_mul(
+            scalars,
+            points.into_iter().map(|p| Some(p.borrow().clone())),
+        )
+        .unwrap()
+    }
+}
+
+impl MultiscalarMul for Secp256k1 {
+    type Point = Secp256k1;
+
+    fn multiscalar_mul<I, J>(scalars: I, points: J) -> Secp256k1
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Fq>,
+        J: IntoIterator,
+        J::Item: Borrow<Secp256k1>,
+    {
+        let mut scalars = scalars.into_iter();
+        let mut points = points.into_iter();
+        let mut acc = Secp256k1::identity();
+        loop {
+            match (scalars.next(), points.next()) {
+                (Some(scalar), Some(point)) => acc += point.borrow() * scalar.borrow(),
+                (None, None) => return acc,
+                _ => panic!("scalars and points must have the same length"),
+            }
+        }
+    }
+}
+
+impl VartimeMultiscalarMul for Secp256k1 {
+    type Point = Secp256k1;
+
+    fn optional_multiscalar_mul<I, J>(scalars: I, points: J) -> Option<Secp256k1>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Fq>,
+        J: IntoIterator<Item = Option<Secp256k1>>,
+    {
+        let scalars: Vec<Fq> = scalars.into_iter().map(|s| *s.borrow()).collect();
+        let points = points.into_iter().collect::<Option<Vec<_>>>()?;
+        assert_eq!(scalars.len(), points.len());
+
+        let mut bases = vec![Secp256k1Affine::default(); points.len()];
+        Secp256k1::batch_normalize(&points, &mut bases);
+        Some(msm(&scalars, &bases))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Bucket {
+    None,
+    Affine(Secp256k1Affine),
+    Projective(Secp256k1),
+}
+
+impl Bucket {
+    fn add_assign(&mut self, other: &Secp256k1Affine) {
+        *self = match *self {
+            Bucket::None => Bucket::Affine(*other),
+            Bucket::Affine(a) => Bucket::Projective(Secp256k1::from(a).add_mixed_vartime(other)),
+            Bucket::Projective(a) => Bucket::Projective(a.add_mixed_vartime(other)),
+        }
+    }
+
+    fn add(self, other: Secp256k1) -> Secp256k1 {
+        match self {
+            Bucket::None => other,
+            Bucket::Affine(a) => other.add_mixed_vartime(&a),
+            Bucket::Projective(a) => other.add_vartime(&a),
+        }
+    }
+}
+
+/// Returns the `c`-bit window of `bytes` starting at bit `segment * c`.
+fn get_at(segment: usize, c: usize, bytes: &[u8; 32]) -> usize {
+    let skip_bits = segment * c;
+    let skip_bytes = skip_bits / 8;
+
+    if skip_bytes >= 32 {
+        return 0;
+    }
+
+    let mut v = [0; 8];
+    for (v, o) in v.iter_mut().zip(bytes[skip_bytes..].iter()) {
+        *v = *o;
+    }
+
+    let mut tmp = u64::from_le_bytes(v);
+    tmp >>= skip_bits - (skip_bytes * 8);
+    tmp %= 1 << c;
+
+    tmp as usize
+}
+
+/// Adds $\sum_i s_i \cdot P_i$ to `acc` using the bucket method. Bases are
+/// only ever added to buckets with mixed addition.
+fn multiexp_serial(scalars: &[[u8; 32]], bases: &[Secp256k1Affine], acc: &mut Secp256k1) {
+    let c = if bases.len() < 4 {
+        1
+    } else if bases.len() < 32 {
+        3
+    } else {
+        (f64::from(bases.len() as u32)).ln().ceil() as usize
+    };
+
+    let mut chunk = Secp256k1::identity();
+    let segments = (256 / c) + 1;
+    let mut buckets = vec![Bucket::None; (1 << c) - 1];
+    for current_segment in (0..segments).rev() {
+        for _ in 0..c {
+            chunk = chunk.double();
+        }
+
+        for bucket in buckets.iter_mut() {
+            *bucket = Bucket::None;
+        }
+        for (scalar, base) in scalars.iter().zip(bases.iter()) {
+            let window = get_at(current_segment, c, scalar);
+            if window != 0 {
+                buckets[window - 1].add_assign(base);
+            }
+        }
+
+        // Summation by parts
+        // e.g. 3a + 2b + 1c = a +
+        //                    (a) + b +
+        //                    ((a) + b) + c
+        let mut running_sum = Secp256k1::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum = bucket.add(running_sum);
+            chunk = chunk.add_vartime(&running_sum);
+        }
+    }
+
+    *acc += chunk;
+}
+
+#[cfg(test)]
+fn naive_msm(scalars: &[Fq], bases: &[Secp256k1Affine]) -> Secp256k1 {
+    scalars
+        .iter()
+        .zip(bases.iter())
+        .fold(Secp256k1::identity(), |acc, (s, p)| acc + p * s)
+}
+
+#[test]
+fn test_msm() {
+    use ff::Field;
+    use group::Curve;
+    use rand::rngs::OsRng;
+
+    assert_eq!(msm(&[], &[]), Secp256k1::identity());
+    for &n in [1, 3, 17, 70].iter() {
+        let scalars: Vec<Fq> = (0..n).map(|_| Fq::random(OsRng)).collect();
+        let bases: Vec<Secp256k1Affine> = (0..n)
+            .map(|_| Secp256k1::random(OsRng).to_affine())
+            .collect();
+        assert_eq!(msm(&scalars, &bases), naive_msm(&scalars, &bases));
+    }
+}
+
+#[test]
+fn test_msm_iter() {
+    use ff::Field;
+    use group::Curve;
+    use rand::rngs::OsRng;
+
+    for &n in [1, 3, 17, 70].iter() {
+        let scalars: Vec<Fq> = (0..n).map(|_| Fq::random(OsRng)).collect();
+        let bases: Vec<Secp256k1Affine> = (0..n)
+            .map(|_| Secp256k1::random(OsRng).to_affine())
+            .collect();
+        let expected = naive_msm(&scalars, &bases);
+
+        for &chunk_size in [1, 5, 128].iter() {
+            let pairs = scalars.iter().cloned().zip(bases.iter().cloned());
+            assert_eq!(msm_iter(pairs, chunk_size), expected);
+        }
+    }
+}
+
+#[test]
+fn test_multiscalar_mul_traits() {
+    use ff::Field;
+    use rand::rngs::OsRng;
+
+    for &n in [0, 1, 3, 17].iter() {
+        let scalars: Vec<Fq> = (0..n).map(|_| Fq::random(OsRng)).collect();
+        let points: Vec<Secp256k1> = (0..n).map(|_| Secp256k1::random(OsRng)).collect();
+        let bases: Vec<Secp256k1Affine> = points.iter().map(|p| p.to_affine()).collect();
+        let expected = naive_msm(&scalars, &bases);
+
+        assert_eq!(Secp256k1::multiscalar_mul(&scalars, &points), expected);
+        assert_eq!(
+            Secp256k1::vartime_multiscalar_mul(scalars.iter(), points.iter()),
+            expected
+        );
+        assert_eq!(
+            Secp256k1::optional_multiscalar_mul(&scalars, points.iter().map(|p| Some(*p))),
+            Some(expected)
+        );
+    }
+
+    let scalar = [Fq::one()];
+    assert_eq!(
+        Secp256k1::optional_multiscalar_mul(&scalar, vec![None]),
+        None
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_multiscalar_mul_length_mismatch() {
+    Secp256k1::multiscalar_mul(&[Fq::one()], &[] as &[Secp256k1]);
+}
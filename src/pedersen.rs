@@ -0,0 +1,431 @@
+//! This module contains a windowed Pedersen hash over secp256k1, following
+//! the construction of the Zcash Sapling Pedersen hash.
+//!
+//! The message is split into 3-bit chunks $(s_0, s_1, s_2)$, each encoded as
+//! the signed digit $(1 - 2 s_2) \cdot (1 + s_0 + 2 s_1)$, and consecutive
+//! runs of [`PEDERSEN_CHUNKS_PER_GENERATOR`] chunks are weighted by powers of
+//! $2^4$ and multiplied by their own generator. The digits are never zero and
+//! the weighted sum of a run is less than $(q - 1) / 2$ in absolute value,
+//! which is what makes the hash cheap to check in-circuit with a lookup per
+//! chunk.
+//!
+//! It also contains Pedersen vector commitments
+//! $C = \sum_i v_i G_i + r H$ over generators derived the same way.
+
+use core::ops::Add;
+use std::vec::Vec;
+
+use ff::{Field, PrimeField};
+use group::{Curve, Group};
+use subtle::{ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+use crate::arithmetic::CurveAffine;
+
+use super::{Fp, Fq, Secp256k1, Secp256k1Affine};
+
+/// The number of 3-bit chunks of the message handled by each generator.
+pub const PEDERSEN_CHUNKS_PER_GENERATOR: usize = 63;
+
+/// The BLAKE2b personalization used to derive the generators.
+const GENERATOR_PERSONALIZATION: &[u8; 16] = b"secp256k1PedGens";
+
+/// The personalization of the generators used by [`commit_vec`].
+const VECTOR_COMMIT_PERSONALIZATION: &[u8] = b"secp256k1-vector-commit";
+
+/// The generator index of the blinding base of a [`VectorCommitmentKey`],
+/// kept apart from the indices of the value generators.
+const BLINDING_INDEX: u32 = u32::MAX;
+
+/// Returns the `index`th generator for `personalization`.
+///
+/// The generator is derived by hashing `personalization`, `index` and a
+/// counter with BLAKE2b into an x-coordinate, incrementing the counter until
+/// the x-coordinate is on the curve, and taking the point with even y. Nobody
+/// knows the discrete logarithm of the result with respect to any other
+/// generator.
+pub fn pedersen_generator(personalization: &[u8], index: u32) -> Secp256k1Affine {
+    for counter in 0u32.. {
+        let hash = blake2b_simd::Params::new()
+            .hash_length(64)
+            .personal(GENERATOR_PERSONALIZATION)
+            .to_state()
+            .update(personalization)
+            .update(&index.to_le_bytes())
+            .update(&counter.to_le_bytes())
+            .finalize();
+
+        let x = Fp::from_bytes_wide(hash.as_array());
+        let y = (x.square() * x + Secp256k1Affine::b()).sqrt();
+        if let Some(y) = Option::<Fp>::from(y) {
+            let y = if bool::from(y.is_odd()) { -y } else { y };
+            return Secp256k1Affine::from_xy(x, y).unwrap();
+        }
+    }
+    unreachable!()
+}
+
+/// Hashes the message `bits` under `personalization`.
+///
+/// The message is padded with zero bits to a multiple of 3, so messages that
+/// differ only in trailing zeros within the last chunk hash to the same
+/// point; callers must fix the message length for each personalization.
+/// This is constant time in the message, but not in its length.
+///
+/// This derives the generators and their tables on every call; build a
+/// [`PedersenHasher`] once to hash repeatedly.
+pub fn pedersen_hash<I: IntoIterator<Item = bool>>(personalization: &[u8], bits: I) -> Secp256k1 {
+    let bits: Vec<bool> = bits.into_iter().collect();
+    PedersenHasher::new(personalization, bits.len()).hash(bits)
+}
+
+/// The generators of [`pedersen_hash`] for one personalization, derived once
+/// and expanded into a table per chunk, so that hashing takes one table
+/// lookup and one addition per chunk and no doublings.
+///
+/// The table of the $i$th chunk of the run of generator $G$ holds
+/// $2^{4i} G, 2 \cdot 2^{4i} G, 3 \cdot 2^{4i} G$ and $4 \cdot 2^{4i} G$, the
+/// multiples by the magnitudes of the chunk digits. That is four affine
+/// points, 256 bytes, per chunk of the longest message.
+#[derive(Clone, Debug)]
+pub struct PedersenHasher {
+    multiples: Vec<Secp256k1Affine>,
+}
+
+impl PedersenHasher {
+    /// Derives the generators for `personalization` and precomputes the
+    /// tables for messages of up to `max_bits` bits.
+    pub fn new(personalization: &[u8], max_bits: usize) -> Self {
+        let num_chunks = (max_bits + 2) / 3;
+        let mut multiples = Vec::with_capacity(4 * num_chunks);
+        let mut base = Secp256k1::identity();
+        for chunk in 0..num_chunks {
+            if chunk % PEDERSEN_CHUNKS_PER_GENERATOR == 0 {
+                let index = (chunk / PEDERSEN_CHUNKS_PER_GENERATOR) as u32;
+                base = pedersen_generator(personalization, index).into();
+            }
+            let double = base.double();
+            let quadruple = double.double();
+            multiples.extend_from_slice(&[base, double, double + base, quadruple]);
+            base = quadruple.double().double();
+        }
+
+        let mut affine = vec![Secp256k1Affine::default(); multiples.len()];
+        Secp256k1::batch_normalize(&multiples, &mut affine);
+        PedersenHasher { multiples: affine }
+    }
+
+    /// Returns the length in bits of the longest message this hasher
+    /// accepts, which is `max_bits` rounded up to a multiple of 3.
+    pub fn max_bits(&self) -> usize {
+        3 * (self.multiples.len() / 4)
+    }
+
+    /// Hashes the message `bits`, with the same result as [`pedersen_hash`]
+    /// under this hasher's personalization. This is constant time in the
+    /// message, but not in its length.
+    ///
+    /// Panics if the message is longer than [`PedersenHasher::max_bits`].
+    pub fn hash<I: IntoIterator<Item = bool>>(&self, bits: I) -> Secp256k1 {
+        let mut bits = bits.into_iter().peekable();
+        let mut tables = self.multiples.chunks(4);
+        let mut acc = Secp256k1::identity();
+        while bits.peek().is_some() {
+            let table = tables.next().expect("message longer than max_bits");
+            let s0 = bits.next().unwrap_or(false);
+            let s1 = bits.next().unwrap_or(false);
+            let s2 = bits.next().unwrap_or(false);
+
+            // Select (1 + s0 + 2 s1) times the chunk base with a full scan.
+            let index = s0 as u64 + 2 * s1 as u64;
+            let mut point = Secp256k1Affine::default();
+            for (i, multiple) in table.iter().enumerate() {
+                point.conditional_assign(multiple, (i as u64).ct_eq(&index));
+            }
+            point.conditional_negate((s2 as u8).into());
+            acc += point;
+        }
+        acc
+    }
+}
+
+/// The generators $G_0, \ldots, G_{n - 1}$ and the blinding base $H$ of
+/// Pedersen vector commitments $C = \sum_i v_i G_i + r H$ to up to $n$
+/// values.
+///
+/// The generators are [`pedersen_generator`]s of one personalization, with
+/// indices $0, \ldots, n - 1$ for the $G_i$ and `u32::MAX` for $H$, so keys
+/// of different lengths for the same personalization agree on their common
+/// generators and on $H$. The commitments are binding as long as nobody knows
+/// a relation between the generators, and hiding when $r$ is uniformly
+/// random.
+#[derive(Clone, Debug)]
+pub struct VectorCommitmentKey {
+    generators: Vec<Secp256k1Affine>,
+    blinding_base: Secp256k1Affine,
+}
+
+impl VectorCommitmentKey {
+    /// Derives the key for committing to up to `len` values under
+    /// `personalization`.
+    ///
+    /// Panics if `len` is not less than `u32::MAX`.
+    pub fn new(personalization: &[u8], len: usize) -> Self {
+        assert!(len < BLINDING_INDEX as usize);
+        VectorCommitmentKey {
+            generators: (0..len as u32)
+                .map(|index| pedersen_generator(personalization, index))
+                .collect(),
+            blinding_base: pedersen_generator(personalization, BLINDING_INDEX),
+        }
+    }
+
+    /// Returns the number of values this key can commit to.
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    /// Returns whether this key commits to no values, only to a blinding
+    /// factor.
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    /// Returns the value generators $G_i$.
+    pub fn generators(&self) -> &[Secp256k1Affine] {
+        &self.generators
+    }
+
+    /// Returns the blinding base $H$.
+    pub fn blinding_base(&self) -> Secp256k1Affine {
+        self.blinding_base
+    }
+
+    /// Commits to `values` with the blinding factor `blinding`. Missing values
+    /// at the end are taken to be zero. This is constant time in `values` and
+    /// `blinding`, but not in the number of values.
+    ///
+    /// Panics if there are more values than generators.
+    pub fn commit(&self, values: &[Fq], blinding: &Fq) -> Secp256k1 {
+        assert!(values.len() <= self.generators.len());
+        values
+            .iter()
+            .zip(self.generators.iter())
+            .fold(self.blinding_base * blinding, |acc, (value, generator)| {
+                acc + generator * value
+            })
+    }
+
+    /// Returns the commitment to `opening`, as [`VectorCommitmentKey::commit`]
+    /// does.
+    pub fn commit_opening(&self, opening: &VectorOpening) -> Secp256k1 {
+        self.commit(&opening.values, &opening.blinding)
+    }
+
+    /// Updates `commitment` after the value at `index` changes from `old` to
+    /// `new`, without the other values or the blinding factor. This is
+    /// constant time in `old` and `new`.
+    ///
+    /// Panics if `index` is not less than [`VectorCommitmentKey::len`].
+    pub fn update(&self, commitment: &Secp256k1, index: usize, old: &Fq, new: &Fq) -> Secp256k1 {
+        commitment + self.generators[index] * (new - old)
+    }
+
+    /// Returns whether `commitment` opens to `opening`. This is constant time
+    /// in the opening, but not in its length.
+    ///
+    /// Returns `false` if the opening has more values than generators.
+    pub fn verify(&self, commitment: &Secp256k1, opening: &VectorOpening) -> bool {
+        opening.values.len() <= self.generators.len()
+            && bool::from(self.commit_opening(opening).ct_eq(commitment))
+    }
+}
+
+/// The values and blinding factor that open a Pedersen vector commitment.
+///
+/// Openings add like the commitments they open: the sum of two commitments
+/// under one key is opened by the sum of their openings, with the shorter
+/// one padded with zeros.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VectorOpening {
+    /// The committed values $v_i$.
+    pub values: Vec<Fq>,
+    /// The blinding factor $r$.
+    pub blinding: Fq,
+}
+
+impl<'a, 'b> Add<&'b VectorOpening> for &'a VectorOpening {
+    type Output = VectorOpening;
+
+    fn add(self, rhs: &'b VectorOpening) -> VectorOpening {
+        let (long, short) = if self.values.len() >= rhs.values.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut values = long.values.clone();
+        for (value, other) in values.iter_mut().zip(short.values.iter()) {
+            *value += other;
+        }
+        VectorOpening {
+            values,
+            blinding: self.blinding + rhs.blinding,
+        }
+    }
+}
+
+impl Add for VectorOpening {
+    type Output = VectorOpening;
+
+    fn add(self, rhs: VectorOpening) -> VectorOpening {
+        &self + &rhs
+    }
+}
+
+/// Commits to `values` with the blinding factor `blinding`, using the
+/// [`VectorCommitmentKey`] of the crate's default personalization
+/// `"secp256k1-vector-commit"` and of length `values.len()`.
+///
+/// This derives the generators on every call; build the key once with
+/// [`VectorCommitmentKey::new`] to commit repeatedly. This is constant time
+/// in `values` and `blinding`, but not in the number of values.
+pub fn commit_vec(values: &[Fq], blinding: &Fq) -> Secp256k1 {
+    VectorCommitmentKey::new(VECTOR_COMMIT_PERSONALIZATION, values.len()).commit(values, blinding)
+}
+
+#[test]
+fn test_pedersen_hash() {
+    use rand::{rngs::OsRng, RngCore};
+    use std::vec::Vec;
+
+    let g0 = pedersen_generator(b"test", 0);
+    let g1 = pedersen_generator(b"test", 1);
+    assert!(bool::from(g0.is_on_curve()));
+    assert_ne!(g0, g1);
+    assert_ne!(g0, pedersen_generator(b"other", 0));
+    assert_eq!(g0, pedersen_generator(b"test", 0));
+
+    assert_eq!(pedersen_hash(b"test", None), Secp256k1::identity());
+
+    // (1, 0, 1) => -(1 + 1), then (0, 1, 0) => 1 + 2 weighted by 2^4.
+    let bits = [true, false, true, false, true, false];
+    let expected = g0 * (-Fq::from(2) + Fq::from(3 * 16));
+    assert_eq!(pedersen_hash(b"test", bits.iter().copied()), expected);
+    assert_eq!(pedersen_hash(b"test", bits[..5].iter().copied()), expected);
+    assert_ne!(pedersen_hash(b"other", bits.iter().copied()), expected);
+
+    // A message spanning two generators.
+    let bits: Vec<bool> = (0..3 * PEDERSEN_CHUNKS_PER_GENERATOR + 3)
+        .map(|_| OsRng.next_u32() & 1 == 1)
+        .collect();
+    let first = pedersen_hash(
+        b"test",
+        bits[..3 * PEDERSEN_CHUNKS_PER_GENERATOR].iter().copied(),
+    );
+    let tail = &bits[3 * PEDERSEN_CHUNKS_PER_GENERATOR..];
+    let digit = Fq::from(1 + tail[0] as u64 + 2 * tail[1] as u64);
+    let digit = if tail[2] { -digit } else { digit };
+    assert_eq!(
+        pedersen_hash(b"test", bits.iter().copied()).to_affine(),
+        (first + g1 * digit).to_affine()
+    );
+}
+
+#[test]
+fn test_pedersen_hasher() {
+    use rand::{rngs::OsRng, RngCore};
+    use std::vec::Vec;
+
+    let max_bits = 3 * PEDERSEN_CHUNKS_PER_GENERATOR * 2 + 1;
+    let hasher = PedersenHasher::new(b"test", max_bits);
+    assert_eq!(hasher.max_bits(), max_bits + 2);
+    assert_eq!(PedersenHasher::new(b"test", 0).max_bits(), 0);
+    assert_eq!(hasher.hash(None), Secp256k1::identity());
+
+    for &len in [1, 3, 5, 3 * PEDERSEN_CHUNKS_PER_GENERATOR + 1, max_bits].iter() {
+        let bits: Vec<bool> = (0..len).map(|_| OsRng.next_u32() & 1 == 1).collect();
+
+        // The sum over each generator of its weighted digits times the
+        // generator, as in the module documentation.
+        let mut expected = Secp256k1::identity();
+        for (index, run) in bits.chunks(3 * PEDERSEN_CHUNKS_PER_GENERATOR).enumerate() {
+            let mut scalar = Fq::zero();
+            for chunk in run.chunks(3).rev() {
+                let s = |i: usize| chunk.get(i).copied().unwrap_or(false);
+                let digit = Fq::from(1 + s(0) as u64 + 2 * s(1) as u64);
+                scalar = scalar * Fq::from(16) + if s(2) { -digit } else { digit };
+            }
+            expected += pedersen_generator(b"test", index as u32) * scalar;
+        }
+
+        assert_eq!(hasher.hash(bits.iter().copied()), expected);
+        assert_eq!(pedersen_hash(b"test", bits.iter().copied()), expected);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_pedersen_hasher_too_long() {
+    PedersenHasher::new(b"test", 3).hash([false; 4].iter().copied());
+}
+
+#[test]
+fn test_vector_commitment() {
+    use rand::rngs::OsRng;
+
+    let key = VectorCommitmentKey::new(b"test", 4);
+    assert_eq!(key.len(), 4);
+    assert!(!key.is_empty());
+    assert_eq!(key.generators()[1], pedersen_generator(b"test", 1));
+    assert_eq!(
+        VectorCommitmentKey::new(b"test", 2).blinding_base(),
+        key.blinding_base()
+    );
+
+    let a = VectorOpening {
+        values: (0..4).map(|_| Fq::random(OsRng)).collect(),
+        blinding: Fq::random(OsRng),
+    };
+    let ca = key.commit_opening(&a);
+    let expected = key.blinding_base() * a.blinding
+        + (0..4)
+            .map(|i| key.generators()[i] * a.values[i])
+            .fold(Secp256k1::identity(), |acc, p| acc + p);
+    assert_eq!(ca, expected);
+    assert!(key.verify(&ca, &a));
+
+    // Changing any part of the opening breaks it.
+    let mut wrong = a.clone();
+    wrong.values[2] += Fq::one();
+    assert!(!key.verify(&ca, &wrong));
+    let mut wrong = a.clone();
+    wrong.blinding += Fq::one();
+    assert!(!key.verify(&ca, &wrong));
+    let mut long = a.clone();
+    long.values.push(Fq::zero());
+    assert!(!key.verify(&ca, &long));
+
+    // Homomorphism, with a shorter opening padded with zeros.
+    let b = VectorOpening {
+        values: (0..2).map(|_| Fq::random(OsRng)).collect(),
+        blinding: Fq::random(OsRng),
+    };
+    let cb = key.commit_opening(&b);
+    assert!(key.verify(&(ca + cb), &(&a + &b)));
+    assert_eq!(&a + &b, b.clone() + a.clone());
+
+    // Updating one value.
+    let new = Fq::random(OsRng);
+    let updated = key.update(&ca, 2, &a.values[2], &new);
+    let mut c = a.clone();
+    c.values[2] = new;
+    assert!(key.verify(&updated, &c));
+
+    assert_eq!(
+        commit_vec(&a.values, &a.blinding),
+        VectorCommitmentKey::new(VECTOR_COMMIT_PERSONALIZATION, 4).commit_opening(&a)
+    );
+    assert_eq!(
+        commit_vec(&[], &a.blinding),
+        pedersen_generator(VECTOR_COMMIT_PERSONALIZATION, BLINDING_INDEX) * a.blinding
+    );
+}
@@ -0,0 +1,88 @@
+//! This module contains utilities for polynomials over a field, represented by
+//! their coefficients in order of increasing degree.
+
+use std::vec::Vec;
+
+use ff::Field;
+
+/// Evaluates the polynomial `poly` at `point` using Horner's rule.
+pub fn eval<F: Field>(poly: &[F], point: F) -> F {
+    poly.iter()
+        .rev()
+        .fold(F::zero(), |acc, coeff| acc * point + coeff)
+}
+
+/// Evaluates the polynomial `poly` at each of `points`.
+pub fn eval_batch<F: Field>(poly: &[F], points: &[F]) -> Vec<F> {
+    points.iter().map(|point| eval(poly, *point)).collect()
+}
+
+/// Evaluates the vanishing polynomial $\prod_i (X - x_i)$ of `points` at
+/// `point`.
+pub fn eval_vanishing<F: Field>(points: &[F], point: F) -> F {
+    points.iter().fold(F::one(), |acc, x| acc * (point - x))
+}
+
+/// Returns the coefficients of the unique polynomial of degree less than
+/// `points.len()` that takes the value `evals[i]` at `points[i]`.
+///
+/// Panics if `points` and `evals` have different lengths, or if `points`
+/// contains duplicates.
+pub fn lagrange_interpolate<F: Field>(points: &[F], evals: &[F]) -> Vec<F> {
+    assert_eq!(points.len(), evals.len());
+
+    let mut res = vec![F::zero(); points.len()];
+    for (j, (x_j, y_j)) in points.iter().zip(evals.iter()).enumerate() {
+        // Build the numerator prod_{k != j} (X - x_k) and the denominator
+        // prod_{k != j} (x_j - x_k) of the jth Lagrange basis polynomial.
+        let mut basis = Vec::with_capacity(points.len());
+        basis.push(F::one());
+        let mut denom = F::one();
+        for (k, x_k) in points.iter().enumerate() {
+            if k == j {
+                continue;
+            }
+            basis.push(F::zero());
+            for i in (1..basis.len()).rev() {
+                basis[i] = basis[i - 1] - basis[i] * x_k;
+            }
+            basis[0] = -basis[0] * x_k;
+            denom *= *x_j - x_k;
+        }
+
+        let scale = denom.invert().expect("points must be distinct") * y_j;
+        for (r, b) in res.iter_mut().zip(basis.iter()) {
+            *r += *b * scale;
+        }
+    }
+    res
+}
+
+#[test]
+fn test_poly() {
+    use crate::Fq;
+    use rand::rngs::OsRng;
+
+    let poly: Vec<Fq> = (0..8).map(|_| Fq::random(OsRng)).collect();
+    let points: Vec<Fq> = (0..8).map(|_| Fq::random(OsRng)).collect();
+    let evals = eval_batch(&poly, &points);
+
+    for (point, e) in points.iter().zip(evals.iter()) {
+        let expected = poly.iter().enumerate().fold(Fq::zero(), |acc, (i, c)| {
+            acc + *c * point.pow_vartime(&[i as u64])
+        });
+        assert_eq!(*e, expected);
+        assert_eq!(eval_vanishing(&points, *point), Fq::zero());
+    }
+
+    assert_eq!(lagrange_interpolate(&points, &evals), poly);
+    assert_eq!(
+        lagrange_interpolate(&points[..1], &evals[..1]),
+        vec![evals[0]]
+    );
+
+    let x = Fq::random(OsRng);
+    let expected = points.iter().fold(Fq::one(), |acc, p| acc * (x - p));
+    assert_eq!(eval_vanishing(&points, x), expected);
+    assert_eq!(eval::<Fq>(&[], x), Fq::zero());
+}
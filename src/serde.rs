@@ -0,0 +1,135 @@
+//! This module contains raw (de)serialization of field elements and points
+//! that copies their internal Montgomery-form limbs directly, for trusted
+//! local caches where canonical encoding and decoding would dominate loading
+//! time.
+
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use core::convert::TryInto;
+use ff::PrimeField;
+
+use super::{Fp, Fq};
+
+/// Raw (de)serialization of the internal representation of an object,
+/// compatible in spirit with `halo2curves::serde::SerdeObject`.
+///
+/// The raw format is the little-endian limbs of the Montgomery form of every
+/// coordinate, with no reduction or compression. It is not portable across
+/// crates and must only be read back from trusted sources by the `unchecked`
+/// functions.
+pub trait SerdeObject: Sized {
+    /// Reads an object from its raw bytes without any validation.
+    ///
+    /// Panics if `bytes` does not have the raw length of the object.
+    fn from_raw_bytes_unchecked(bytes: &[u8]) -> Self;
+
+    /// Reads an object from its raw bytes, returning `None` if `bytes` does
+    /// not have the raw length of the object or does not encode a valid one.
+    fn from_raw_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// Returns the raw bytes of the object.
+    fn to_raw_bytes(&self) -> Vec<u8>;
+
+    /// Reads an object from its raw bytes without any validation.
+    ///
+    /// Panics if the reader fails.
+    fn read_raw_unchecked<R: Read>(reader: &mut R) -> Self;
+
+    /// Reads an object from its raw bytes, failing with
+    /// `io::ErrorKind::InvalidData` if it is not valid.
+    fn read_raw<R: Read>(reader: &mut R) -> io::Result<Self>;
+
+    /// Writes the raw bytes of the object.
+    fn write_raw<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+pub(crate) fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid raw encoding")
+}
+
+macro_rules! impl_serde_object_field {
+    ($field:ident) => {
+        impl SerdeObject for $field {
+            fn from_raw_bytes_unchecked(bytes: &[u8]) -> Self {
+                assert_eq!(bytes.len(), 32);
+                let mut limbs = [0u64; 4];
+                for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+                    *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+                }
+                $field(limbs)
+            }
+
+            fn from_raw_bytes(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != 32 {
+                    return None;
+                }
+                // The limbs are valid if they are fully reduced, which is
+                // exactly what `from_repr` checks of its input.
+                let repr: [u8; 32] = bytes.try_into().unwrap();
+                if bool::from($field::from_repr(repr).is_none()) {
+                    return None;
+                }
+                Some(Self::from_raw_bytes_unchecked(bytes))
+            }
+
+            fn to_raw_bytes(&self) -> Vec<u8> {
+                let mut res = Vec::with_capacity(32);
+                for limb in self.0.iter() {
+                    res.extend_from_slice(&limb.to_le_bytes());
+                }
+                res
+            }
+
+            fn read_raw_unchecked<R: Read>(reader: &mut R) -> Self {
+                let mut bytes = [0u8; 32];
+                reader.read_exact(&mut bytes).unwrap();
+                Self::from_raw_bytes_unchecked(&bytes)
+            }
+
+            fn read_raw<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let mut bytes = [0u8; 32];
+                reader.read_exact(&mut bytes)?;
+                Self::from_raw_bytes(&bytes).ok_or_else(invalid_data)
+            }
+
+            fn write_raw<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                for limb in self.0.iter() {
+                    writer.write_all(&limb.to_le_bytes())?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_serde_object_field!(Fp);
+impl_serde_object_field!(Fq);
+
+#[test]
+fn test_serde_object_field() {
+    use ff::Field;
+    use rand::rngs::OsRng;
+
+    fn check<F: Field + SerdeObject>() {
+        for a in [F::zero(), F::one(), -F::one(), F::random(OsRng)].iter() {
+            let bytes = a.to_raw_bytes();
+            assert_eq!(bytes.len(), 32);
+            assert_eq!(F::from_raw_bytes_unchecked(&bytes), *a);
+            assert_eq!(F::from_raw_bytes(&bytes), Some(*a));
+
+            let mut buf = Vec::new();
+            a.write_raw(&mut buf).unwrap();
+            assert_eq!(buf, bytes);
+            assert_eq!(F::read_raw(&mut &buf[..]).unwrap(), *a);
+            assert_eq!(F::read_raw_unchecked(&mut &buf[..]), *a);
+        }
+
+        assert_eq!(F::from_raw_bytes(&[0xff; 32]), None);
+        assert_eq!(F::from_raw_bytes(&[0; 31]), None);
+        assert!(F::read_raw(&mut &[0xff; 32][..]).is_err());
+    }
+
+    check::<Fp>();
+    check::<Fq>();
+}
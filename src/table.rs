@@ -0,0 +1,172 @@
+//! This module contains a precomputed table of odd multiples of a point for
+//! repeated scalar multiplication by the same base.
+
+use std::vec::Vec;
+
+use group::{Curve, Group};
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+use crate::arithmetic::adc;
+use crate::wnaf::{wnaf_digits, wnaf_sum_vartime};
+
+use super::{Fq, Secp256k1, Secp256k1Affine};
+
+/// The odd multiples $P, 3P, \ldots, (2^W - 1)P$ of a base point $P$, stored in
+/// affine form for mixed addition.
+///
+/// `W` must be between 1 and 8; the table holds $2^{W - 1}$ points.
+///
+/// The width trades memory for speed. A table takes $2^{W - 1}$ affine points
+/// of 64 bytes, from 64 bytes at `W = 1` to 8 KiB at `W = 8`, and costs as many
+/// additions to build. Each multiplication then takes about $256 / W$
+/// additions on top of its 256 doublings. Small widths suit constrained
+/// targets and one-off bases; large widths suit many multiplications by one
+/// base. For tables without an allocator, see [`crate::Wnaf`].
+#[derive(Clone, Debug)]
+pub struct WindowTable<const W: usize> {
+    odd_multiples: Vec<Secp256k1Affine>,
+}
+
+impl<const W: usize> WindowTable<W> {
+    /// Precomputes the table for `base`.
+    ///
+    /// Panics if `W` is not between 1 and 8.
+    pub fn new(base: &Secp256k1) -> Self {
+        assert!((1..=8).contains(&W));
+
+        let double = base.double();
+        let mut projective = Vec::with_capacity(1 << (W - 1));
+        projective.push(*base);
+        for i in 1..(1 << (W - 1)) {
+            projective.push(projective[i - 1] + double);
+        }
+
+        let mut odd_multiples = vec![Secp256k1Affine::default(); projective.len()];
+        Secp256k1::batch_normalize(&projective, &mut odd_multiples);
+
+        WindowTable { odd_multiples }
+    }
+
+    /// Returns `digit * P` for an odd `digit` with $|digit| < 2^W$, scanning
+    /// the whole table so that the access pattern does not depend on `digit`.
+    fn select(&self, digit: i16) -> Secp256k1Affine {
+        let sign = (digit >> 15) as u16;
+        let abs = ((digit as u16) ^ sign).wrapping_sub(sign);
+        let index = (abs >> 1) as u64;
+
+        let mut res = Secp256k1Affine::default();
+        for (i, point) in self.odd_multiples.iter().enumerate() {
+            res.conditional_assign(point, (i as u64).ct_eq(&index));
+        }
+        res.conditional_negate(Choice::from((sign & 1) as u8));
+        res
+    }
+
+    /// Multiplies the base point by `scalar`.
+    ///
+    /// The scalar is recoded into odd signed digits so that every window
+    /// performs one doubling run and one addition, and table entries are
+    /// selected with a full scan. Neither the recoding nor the table lookups
+    /// branch on or index memory by the scalar.
+    pub fn mul(&self, scalar: &Fq) -> Secp256k1 {
+        let mut k = scalar.to_canonical_limbs();
+
+        // The recoding needs an odd scalar: multiply by k + 1 instead when k
+        // is even, and subtract the base once at the end. k + 1 <= q < 2^256.
+        let is_even = 1 - (k[0] & 1);
+        let mut carry = is_even;
+        for limb in k.iter_mut() {
+            let (l, c) = adc(*limb, 0, carry);
+            *limb = l;
+            carry = c;
+        }
+
+        // k = sum(d_i * 2^(W * i)) with every d_i odd and |d_i| < 2^W.
+        let num_digits = (256 + W - 1) / W + 1;
+        let mut digits = vec![0i16; num_digits];
+        for digit in digits.iter_mut().take(num_digits - 1) {
+            *digit = (k[0] & ((1 << (W + 1)) - 1)) as i16 - (1 << W);
+            // k = (k - d) / 2^W = 2 * (k >> (W + 1)) + 1
+            shr(&mut k, W + 1);
+            shl1_or1(&mut k);
+        }
+        digits[num_digits - 1] = k[0] as i16;
+
+        let mut acc = Secp256k1::from(self.select(digits[num_digits - 1]));
+        for &digit in digits.iter().rev().skip(1) {
+            for _ in 0..W {
+                acc = acc.double();
+            }
+            acc += self.select(digit);
+        }
+
+        let base = self.odd_multiples[0];
+        acc = Secp256k1::conditional_select(&acc, &(acc - base), Choice::from(is_even as u8));
+
+        // The recoded scalar may be secret, so it is wiped before returning.
+        #[cfg(feature = "zeroize")]
+        {
+            zeroize::Zeroize::zeroize(&mut k);
+            zeroize::Zeroize::zeroize(&mut digits[..]);
+        }
+
+        acc
+    }
+
+    /// Multiplies the base point by `scalar` using wNAF. This is not constant
+    /// time.
+    pub fn mul_vartime(&self, scalar: &Fq) -> Secp256k1 {
+        // Width-(W + 1) NAF: nonzero digits are odd with |d| < 2^W.
+        wnaf_sum_vartime(&[(
+            &self.odd_multiples,
+            wnaf_digits(scalar.to_canonical_limbs(), W + 1),
+        )])
+    }
+}
+
+/// Shifts `k` right by `s < 64` bits.
+fn shr(k: &mut [u64], s: usize) {
+    for i in 0..k.len() {
+        let hi = if i + 1 < k.len() {
+            k[i + 1] << (63 - s) << 1
+        } else {
+            0
+        };
+        k[i] = (k[i] >> s) | hi;
+    }
+}
+
+/// Sets `k` to `2k + 1`.
+fn shl1_or1(k: &mut [u64]) {
+    let mut carry = 1;
+    for limb in k.iter_mut() {
+        let next = *limb >> 63;
+        *limb = (*limb << 1) | carry;
+        carry = next;
+    }
+}
+
+#[test]
+fn test_window_table() {
+    use ff::Field;
+    use rand::rngs::OsRng;
+
+    fn check<const W: usize>(base: &Secp256k1, scalars: &[Fq]) {
+        let table = WindowTable::<W>::new(base);
+        for scalar in scalars {
+            let expected = base * scalar;
+            assert_eq!(table.mul(scalar), expected);
+            assert_eq!(table.mul_vartime(scalar), expected);
+        }
+    }
+
+    let base = Secp256k1::random(OsRng);
+    let mut scalars = vec![Fq::zero(), Fq::one(), Fq::from(2), -Fq::one(), -Fq::from(2)];
+    scalars.extend((0..20).map(|_| Fq::random(OsRng)));
+
+    check::<1>(&base, &scalars);
+    check::<2>(&base, &scalars);
+    check::<4>(&base, &scalars);
+    check::<5>(&base, &scalars);
+    check::<8>(&base, &scalars);
+}
@@ -0,0 +1,95 @@
+//! This module contains helpers for BIP-340 x-only public keys and BIP-341
+//! key tweaking. Keys, coordinates and tweaks are 32-byte big-endian strings,
+//! as in those BIPs.
+
+use ff::{Field, PrimeField};
+use group::{Curve, GroupEncoding};
+use subtle::{ConditionallySelectable, CtOption};
+
+use super::{Fp, Fq, Secp256k1, Secp256k1Affine, Serialized};
+
+/// Returns the point with x-coordinate `x` and even y, as `lift_x` in BIP-340,
+/// failing if `x` is not less than $p$ or is not the x-coordinate of a point.
+pub fn lift_x(x: &[u8; 32]) -> CtOption<Secp256k1Affine> {
+    Fp::from_repr_be(x).and_then(|x| {
+        (x.square() * x + Fp::from(7)).sqrt().and_then(|y| {
+            let y = Fp::conditional_select(&y, &-y, y.is_odd());
+            let mut repr = Serialized::default();
+            repr.as_mut()[..32].copy_from_slice(&x.to_repr());
+            repr.as_mut()[32..].copy_from_slice(&y.to_repr());
+            Secp256k1Affine::from_bytes(&repr)
+        })
+    })
+}
+
+/// Checks that `output_key`, whose y-coordinate has parity `parity` (`true`
+/// for odd), is the x-only key `internal_key` tweaked by `tweak`, that is
+/// $Q = \mathrm{lift\_x}(P) + t \cdot G$. This matches
+/// `secp256k1_xonly_pubkey_tweak_add_check` in libsecp256k1.
+///
+/// Returns `false` if `internal_key` is not a valid x-only key, if `tweak` is
+/// not less than the group order, or if the tweaked point is the identity.
+/// This is not constant time: every input is public.
+pub fn tweak_add_check(
+    output_key: &[u8; 32],
+    parity: bool,
+    internal_key: &[u8; 32],
+    tweak: &[u8; 32],
+) -> bool {
+    let (internal, tweak) = match (
+        Option::from(lift_x(internal_key)),
+        Option::<Fq>::from(Fq::from_repr_be(tweak)),
+    ) {
+        (Some(internal), Some(tweak)) => (internal, tweak),
+        _ => return false,
+    };
+
+    let output = Secp256k1::generator()
+        .mul_vartime(&tweak)
+        .add_mixed_vartime(&internal)
+        .to_affine();
+    if bool::from(output.is_identity()) {
+        return false;
+    }
+
+    let bytes = output.to_bytes();
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&bytes.as_ref()[..32]);
+    x.reverse();
+    let y_is_odd = bytes.as_ref()[32] & 1 == 1;
+
+    x == *output_key && y_is_odd == parity
+}
+
+#[cfg(test)]
+fn from_hex(s: &str) -> [u8; 32] {
+    let mut res = [0u8; 32];
+    for (i, b) in res.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+    }
+    res
+}
+
+#[test]
+fn test_tweak_add_check() {
+    // From the BIP-341 wallet test vectors.
+    let internal = from_hex("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+    let tweak = from_hex("b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70");
+    let output = from_hex("53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343");
+
+    assert!(tweak_add_check(&output, true, &internal, &tweak));
+    assert!(!tweak_add_check(&output, false, &internal, &tweak));
+    assert!(!tweak_add_check(&internal, true, &internal, &tweak));
+
+    let mut wrong_tweak = tweak;
+    wrong_tweak[31] ^= 1;
+    assert!(!tweak_add_check(&output, true, &internal, &wrong_tweak));
+    assert!(!tweak_add_check(&output, true, &internal, &[0xff; 32]));
+    assert!(!tweak_add_check(&output, true, &[0xff; 32], &tweak));
+
+    let g = lift_x(&from_hex(
+        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    ))
+    .unwrap();
+    assert_eq!(g, Secp256k1Affine::generator());
+}
@@ -0,0 +1,82 @@
+//! This module contains scalar multiplication that records its intermediate
+//! points, for use as witness values in circuits.
+
+use std::vec::Vec;
+
+use ff::PrimeField;
+use group::Group;
+use subtle::{Choice, ConditionallySelectable};
+
+use super::{Fq, Secp256k1};
+
+/// One step of the double-and-add performed by [`Secp256k1::mul_with_trace`].
+#[derive(Clone, Copy, Debug)]
+pub struct MulStep {
+    /// The bit of the scalar consumed by this step.
+    pub bit: bool,
+    /// The base doubled `i` times, $2^i \cdot P$, for the `i`th step.
+    pub base: Secp256k1,
+    /// The accumulator after this step, $(k \bmod 2^{i + 1}) \cdot P$.
+    pub acc: Secp256k1,
+}
+
+impl Secp256k1 {
+    /// Multiplies this point by `scalar`, returning the result together with
+    /// one [`MulStep`] per bit of the scalar, least significant bit first.
+    ///
+    /// The arithmetic performed does not depend on the scalar, but the trace
+    /// of course does: it is meant for witness generation, not for handling
+    /// secrets outside a prover.
+    pub fn mul_with_trace(&self, scalar: &Fq) -> (Secp256k1, Vec<MulStep>) {
+        let mut trace = Vec::with_capacity(256);
+
+        let mut base = *self;
+        let mut acc = Secp256k1::identity();
+        // The scalar may be secret, so its bytes are wiped afterwards.
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut repr = scalar.to_repr();
+        for byte in repr.iter() {
+            for i in 0..8 {
+                let bit = (byte >> i) & 1;
+                acc = Secp256k1::conditional_select(&acc, &(acc + base), Choice::from(bit));
+                trace.push(MulStep {
+                    bit: bit == 1,
+                    base,
+                    acc,
+                });
+                base = base.double();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut repr);
+
+        (acc, trace)
+    }
+}
+
+#[test]
+fn test_mul_with_trace() {
+    use ff::Field;
+    use rand::rngs::OsRng;
+
+    let p = Secp256k1::random(OsRng);
+    for k in [Fq::zero(), Fq::one(), -Fq::one(), Fq::random(OsRng)].iter() {
+        let (res, trace) = p.mul_with_trace(k);
+        assert_eq!(res, p * k);
+        assert_eq!(trace.len(), 256);
+        assert_eq!(trace.last().unwrap().acc, res);
+
+        let mut partial = Fq::zero();
+        let mut power = Fq::one();
+        for step in trace.iter() {
+            if step.bit {
+                partial += power;
+            }
+            assert_eq!(step.base, p * power);
+            assert_eq!(step.acc, p * partial);
+            power = power.double();
+        }
+        assert_eq!(partial, *k);
+    }
+}
@@ -0,0 +1,277 @@
+//! This module contains width-`w` NAF (wNAF) point multiplication with the
+//! table size fixed at compile time, which needs no allocator.
+
+use core::cmp::Ordering;
+
+use group::{Curve, Group};
+
+use crate::arithmetic::{adc, sbb};
+
+use super::{Fq, Secp256k1, Secp256k1Affine};
+
+/// The largest window width supported by [`Wnaf`].
+pub const WNAF_MAX_WIDTH: usize = 8;
+
+/// The number of digits in the wNAF form of a scalar.
+pub const WNAF_NUM_DIGITS: usize = 257;
+
+const MAX_TABLE_SIZE: usize = 1 << (WNAF_MAX_WIDTH - 2);
+
+#[cfg(feature = "precomputed-tables")]
+mod generated {
+    use super::Secp256k1Affine;
+
+    include!(concat!(env!("OUT_DIR"), "/generator_wnaf.rs"));
+}
+
+/// The size of the tables [`Wnaf::GENERATOR`] and [`Wnaf::GENERATOR_ENDO`],
+/// $2^{w - 2}$ for the window width $w$ set by the
+/// `SECP256K1_GENERATOR_WNAF_WIDTH` environment variable at build time, 8 by
+/// default.
+#[cfg(feature = "precomputed-tables")]
+pub const GENERATOR_WNAF_TABLE_SIZE: usize = generated::GENERATOR_WNAF_TABLE_SIZE;
+
+/// The `N` odd multiples $P, 3P, \ldots, (2N - 1)P$ of a base point $P$,
+/// stored in affine form for wNAF multiplication with window width
+/// $w = \log_2 N + 2$ ([`Wnaf::WIDTH`]).
+///
+/// `N` must be a power of two no larger than $2^{w_{max} - 2} = 64$, where
+/// $w_{max}$ is [`WNAF_MAX_WIDTH`]: `Wnaf<1>` has width 2 and `Wnaf<64>`
+/// width 8. The table is an array of exactly `N` points, so it can live on
+/// the stack; it takes about $64 N$ bytes, and [`Wnaf::new`] needs another
+/// $96 N$ bytes of stack while it builds it. `N` is only checked when a table
+/// is built, so an unsupported size panics at runtime rather than failing to
+/// compile.
+///
+/// `N` trades the size and cost of building the table, $N$ additions,
+/// against the cost of each multiplication, about $256 / (w + 1)$ additions
+/// on top of the doublings. Small tables suit one-off multiplications;
+/// `Wnaf<64>` suits many multiplications by one base.
+#[derive(Clone, Debug)]
+pub struct Wnaf<const N: usize> {
+    odd_multiples: [Secp256k1Affine; N],
+}
+
+impl<const N: usize> Wnaf<N> {
+    /// The window width, $\log_2 N + 2$.
+    pub const WIDTH: usize = N.trailing_zeros() as usize + 2;
+
+    /// Precomputes the table for `base`.
+    ///
+    /// Panics if `N` is not a power of two or is larger than 64.
+    pub fn new(base: &Secp256k1) -> Self {
+        assert!(N.is_power_of_two() && N <= MAX_TABLE_SIZE);
+
+        let double = base.double();
+        let mut projective = [*base; N];
+        for i in 1..N {
+            projective[i] = projective[i - 1] + double;
+        }
+
+        let mut odd_multiples = [Secp256k1Affine::default(); N];
+        Secp256k1::batch_normalize(&projective, &mut odd_multiples);
+
+        Wnaf { odd_multiples }
+    }
+
+    /// Returns the width-[`Wnaf::WIDTH`] NAF of `scalar`, least significant
+    /// digit first. Every nonzero digit is odd and less than $2N$ in absolute
+    /// value, and is followed by at least `WIDTH - 1` zero digits. This is
+    /// not constant time.
+    pub fn digits(scalar: &Fq) -> [i8; WNAF_NUM_DIGITS] {
+        let naf = wnaf_digits(scalar.to_canonical_limbs(), Self::WIDTH);
+        let mut digits = [0i8; WNAF_NUM_DIGITS];
+        for (digit, &d) in digits.iter_mut().zip(naf.iter()) {
+            *digit = d as i8;
+        }
+        digits
+    }
+
+    /// Returns the table for $\lambda P$, where $P$ is the base and
+    /// $\lambda$ = `Fq::ZETA`, by applying [`Secp256k1Affine::endo`] to every
+    /// entry. This costs one field multiplication per entry.
+    pub fn endo(&self) -> Self {
+        let mut odd_multiples = self.odd_multiples;
+        for point in odd_multiples.iter_mut() {
+            *point = point.endo();
+        }
+        Wnaf { odd_multiples }
+    }
+
+    /// Returns the odd multiples $P, 3P, \ldots$
+    pub(crate) fn odd_multiples(&self) -> &[Secp256k1Affine] {
+        &self.odd_multiples
+    }
+
+    /// Multiplies the base point by `scalar`. This is not constant time.
+    pub fn mul_vartime(&self, scalar: &Fq) -> Secp256k1 {
+        wnaf_sum_vartime(&[(
+            self.odd_multiples(),
+            wnaf_digits(scalar.to_canonical_limbs(), Self::WIDTH),
+        )])
+    }
+}
+
+#[cfg(feature = "precomputed-tables")]
+impl Wnaf<{ GENERATOR_WNAF_TABLE_SIZE }> {
+    /// The table for the generator $G$, generated by the build script.
+    pub const GENERATOR: Self = Wnaf {
+        odd_multiples: generated::GENERATOR_ODD_MULTIPLES,
+    };
+
+    /// The table for $\lambda G$, as [`Wnaf::endo`] returns for
+    /// [`Wnaf::GENERATOR`], generated by the build script.
+    pub const GENERATOR_ENDO: Self = Wnaf {
+        odd_multiples: generated::GENERATOR_ENDO_ODD_MULTIPLES,
+    };
+}
+
+/// Computes the sum of the products described by `terms`, each a table of
+/// the odd multiples $P, 3P, \ldots$ of a base $P$ and the wNAF digits of its
+/// scalar, least significant first. The digits of each term must be odd and
+/// small enough to index its table.
+///
+/// All the terms share one run of doublings. This is not constant time.
+pub(crate) fn wnaf_sum_vartime(
+    terms: &[(&[Secp256k1Affine], [i16; WNAF_NUM_DIGITS])],
+) -> Secp256k1 {
+    let len = terms
+        .iter()
+        .filter_map(|(_, digits)| digits.iter().rposition(|&d| d != 0))
+        .max()
+        .map_or(0, |top| top + 1);
+
+    let mut acc = Secp256k1::identity();
+    for i in (0..len).rev() {
+        acc = acc.double();
+        for (odd_multiples, digits) in terms.iter() {
+            let digit = digits[i];
+            match digit.cmp(&0) {
+                Ordering::Greater => {
+                    acc = acc.add_mixed_vartime(&odd_multiples[(digit >> 1) as usize])
+                }
+                Ordering::Less => {
+                    acc = acc.add_mixed_vartime(&-odd_multiples[((-digit) >> 1) as usize])
+                }
+                Ordering::Equal => {}
+            }
+        }
+    }
+    acc
+}
+
+/// Returns the width-`width` NAF of the 256-bit integer `limbs`, least
+/// significant digit first. `width` must be between 2 and 15 so that every
+/// digit fits an `i16`. This is not constant time.
+pub(crate) fn wnaf_digits(limbs: [u64; 4], width: usize) -> [i16; WNAF_NUM_DIGITS] {
+    let mut k = [limbs[0], limbs[1], limbs[2], limbs[3], 0];
+
+    let mut digits = [0i16; WNAF_NUM_DIGITS];
+    for digit in digits.iter_mut() {
+        if k[0] & 1 == 1 {
+            let mut d = (k[0] & ((1 << width) - 1)) as i64;
+            if d >= 1 << (width - 1) {
+                d -= 1 << width;
+            }
+
+            // Subtract d from k, leaving it divisible by 2^width.
+            let (l, mut carry) = if d > 0 {
+                sbb(k[0], d as u64, 0)
+            } else {
+                adc(k[0], (-d) as u64, 0)
+            };
+            k[0] = l;
+            for limb in k.iter_mut().skip(1) {
+                let (l, c) = if d > 0 {
+                    sbb(*limb, 0, carry)
+                } else {
+                    adc(*limb, 0, carry)
+                };
+                *limb = l;
+                carry = c;
+            }
+            *digit = d as i16;
+        }
+
+        for i in 0..4 {
+            k[i] = (k[i] >> 1) | (k[i + 1] << 63);
+        }
+        k[4] >>= 1;
+    }
+    digits
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_wnaf() {
+    use ff::Field;
+    use rand::rngs::OsRng;
+
+    fn check<const N: usize>(base: &Secp256k1, scalars: &[Fq]) {
+        let wnaf = Wnaf::<N>::new(base);
+        let w = Wnaf::<N>::WIDTH;
+        assert_eq!(1 << (w - 2), N);
+        for scalar in scalars.iter() {
+            assert_eq!(wnaf.mul_vartime(scalar), base * scalar);
+
+            let digits = Wnaf::<N>::digits(scalar);
+            let mut recomposed = Fq::zero();
+            for (i, &digit) in digits.iter().enumerate().rev() {
+                let term = Fq::from(digit.abs() as u64);
+                recomposed = recomposed.double() + if digit < 0 { -term } else { term };
+                if digit != 0 {
+                    assert_eq!(digit & 1, 1);
+                    assert!((digit as i64).abs() < 2 * N as i64);
+                    let next = &digits[i + 1..(i + w).min(WNAF_NUM_DIGITS)];
+                    assert!(next.iter().all(|&d| d == 0));
+                }
+            }
+            assert_eq!(recomposed, *scalar);
+        }
+    }
+
+    let base = Secp256k1::random(OsRng);
+    let scalars = [
+        Fq::zero(),
+        Fq::one(),
+        -Fq::one(),
+        Fq::random(OsRng),
+        Fq::random(OsRng),
+    ];
+    check::<1>(&base, &scalars);
+    check::<2>(&base, &scalars);
+    check::<4>(&base, &scalars);
+    check::<8>(&base, &scalars);
+    check::<16>(&base, &scalars);
+    check::<32>(&base, &scalars);
+    check::<64>(&base, &scalars);
+}
+
+#[cfg(feature = "precomputed-tables")]
+#[test]
+fn test_generated_tables() {
+    type Table = Wnaf<{ GENERATOR_WNAF_TABLE_SIZE }>;
+
+    let generator = Table::new(&Secp256k1::generator());
+    assert_eq!(Table::GENERATOR.odd_multiples(), generator.odd_multiples());
+    assert_eq!(
+        Table::GENERATOR_ENDO.odd_multiples(),
+        generator.endo().odd_multiples()
+    );
+    assert!(Table::GENERATOR
+        .odd_multiples()
+        .iter()
+        .all(|point| bool::from(point.is_on_curve())));
+}
+
+#[test]
+#[should_panic]
+fn test_wnaf_too_large() {
+    Wnaf::<128>::new(&Secp256k1::generator());
+}
+
+#[test]
+#[should_panic]
+fn test_wnaf_not_power_of_two() {
+    Wnaf::<3>::new(&Secp256k1::generator());
+}